@@ -0,0 +1,456 @@
+#[allow(clippy::module_inception)]
+pub mod bigint {
+    use std::cmp::Ordering;
+    use std::convert::TryFrom;
+    use std::fmt;
+
+    /// An integer of any size, as a sign and a magnitude of 32 bit limbs,
+    /// least significant first and without leading zero limbs. Zero has
+    /// no limbs and is never negative.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct BigInt {
+        negative: bool,
+        magnitude: Vec<u32>,
+    }
+
+    fn trim(magnitude: &mut Vec<u32>) {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+    }
+
+    fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+        a.len()
+            .cmp(&b.len())
+            .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+    }
+
+    fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..a.len().max(b.len()) {
+            let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+            result.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            result.push(carry as u32);
+        }
+        result
+    }
+
+    // a - b where a >= b
+    fn substract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len());
+        let mut borrow = 0i64;
+        for (i, x) in a.iter().enumerate() {
+            let mut difference = *x as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = if difference < 0 {
+                difference += 1 << 32;
+                1
+            } else {
+                0
+            };
+            result.push(difference as u32);
+        }
+        trim(&mut result);
+        result
+    }
+
+    fn multiply_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = vec![0u32; a.len() + b.len()];
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, y) in b.iter().enumerate() {
+                let product = *x as u64 * *y as u64 + result[i + j] as u64 + carry;
+                result[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            result[i + b.len()] = carry as u32;
+        }
+        trim(&mut result);
+        result
+    }
+
+    // the quotient and remainder of a division by a single limb
+    fn divide_magnitude(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+        let mut quotient = vec![0u32; a.len()];
+        let mut remainder = 0u64;
+        for i in (0..a.len()).rev() {
+            let current = (remainder << 32) | a[i] as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        trim(&mut quotient);
+        (quotient, remainder as u32)
+    }
+
+    #[allow(dead_code)]
+    impl BigInt {
+        fn new(negative: bool, mut magnitude: Vec<u32>) -> Self {
+            trim(&mut magnitude);
+            let negative = negative && !magnitude.is_empty();
+            BigInt {
+                negative,
+                magnitude,
+            }
+        }
+
+        pub fn from_i128(n: i128) -> Self {
+            let mut rest = n.unsigned_abs();
+            let mut magnitude = vec![];
+            while rest > 0 {
+                magnitude.push(rest as u32);
+                rest >>= 32;
+            }
+            BigInt::new(n < 0, magnitude)
+        }
+
+        /// The value as an i64, if it fits.
+        pub fn to_i64(&self) -> Option<i64> {
+            if self.magnitude.len() > 2 {
+                return None;
+            }
+            let mut n = 0i128;
+            for limb in self.magnitude.iter().rev() {
+                n = (n << 32) | *limb as i128;
+            }
+            i64::try_from(if self.negative { -n } else { n }).ok()
+        }
+
+        /// The low 64 bits of the two's complement form.
+        pub fn low_u64(&self) -> u64 {
+            let limbs = self.to_twos_complement(self.magnitude.len().max(2) + 1);
+            limbs[0] as u64 | (limbs[1] as u64) << 32
+        }
+
+        pub fn to_f64(&self) -> f64 {
+            let mut f = 0.0;
+            for limb in self.magnitude.iter().rev() {
+                f = f * 4294967296.0 + *limb as f64;
+            }
+            if self.negative {
+                -f
+            } else {
+                f
+            }
+        }
+
+        /// Reads an optionally signed string of decimal digits.
+        pub fn parse(s: &str) -> Option<Self> {
+            let (negative, digits) = match s.strip_prefix('-') {
+                Some(x) => (true, x),
+                None => (false, s.strip_prefix('+').unwrap_or(s)),
+            };
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let mut magnitude = vec![];
+            for c in digits.chars() {
+                magnitude = multiply_magnitudes(&magnitude, &[10]);
+                magnitude = add_magnitudes(&magnitude, &[c.to_digit(10).unwrap()]);
+            }
+            Some(BigInt::new(negative, magnitude))
+        }
+
+        pub fn is_negative(&self) -> bool {
+            self.negative
+        }
+
+        pub fn is_odd(&self) -> bool {
+            self.magnitude.first().is_some_and(|x| x & 1 == 1)
+        }
+
+        pub fn negate(&self) -> Self {
+            BigInt::new(!self.negative, self.magnitude.clone())
+        }
+
+        pub fn abs(&self) -> Self {
+            BigInt::new(false, self.magnitude.clone())
+        }
+
+        pub fn add(&self, other: &BigInt) -> Self {
+            if self.negative == other.negative {
+                BigInt::new(
+                    self.negative,
+                    add_magnitudes(&self.magnitude, &other.magnitude),
+                )
+            } else {
+                match compare_magnitudes(&self.magnitude, &other.magnitude) {
+                    Ordering::Less => BigInt::new(
+                        other.negative,
+                        substract_magnitudes(&other.magnitude, &self.magnitude),
+                    ),
+                    _ => BigInt::new(
+                        self.negative,
+                        substract_magnitudes(&self.magnitude, &other.magnitude),
+                    ),
+                }
+            }
+        }
+
+        pub fn substract(&self, other: &BigInt) -> Self {
+            self.add(&other.negate())
+        }
+
+        pub fn multiply(&self, other: &BigInt) -> Self {
+            BigInt::new(
+                self.negative != other.negative,
+                multiply_magnitudes(&self.magnitude, &other.magnitude),
+            )
+        }
+
+        /// The truncated quotient and the remainder, which has the sign
+        /// of the dividend.
+        pub fn divide(&self, other: &BigInt) -> (Self, Self) {
+            if other.magnitude.is_empty() {
+                panic!("divide by zero!");
+            }
+            let (quotient, remainder) = if other.magnitude.len() == 1 {
+                let (q, r) = divide_magnitude(&self.magnitude, other.magnitude[0]);
+                (q, vec![r])
+            } else {
+                // one bit at a time, from the most significant
+                let divisor = other.abs();
+                let mut quotient = vec![0u32; self.magnitude.len()];
+                let mut remainder = BigInt::from_i128(0);
+                for i in (0..self.magnitude.len() * 32).rev() {
+                    remainder = remainder.shift(1);
+                    if (self.magnitude[i / 32] >> (i % 32)) & 1 == 1 {
+                        remainder = remainder.add(&BigInt::from_i128(1));
+                    }
+                    if remainder >= divisor {
+                        remainder = remainder.substract(&divisor);
+                        quotient[i / 32] |= 1 << (i % 32);
+                    }
+                }
+                (quotient, remainder.magnitude)
+            };
+            (
+                BigInt::new(self.negative != other.negative, quotient),
+                BigInt::new(self.negative, remainder),
+            )
+        }
+
+        // limbs of the two's complement form, long enough to hold the
+        // sign in the last limb
+        fn to_twos_complement(&self, length: usize) -> Vec<u32> {
+            let mut limbs = self.magnitude.clone();
+            limbs.resize(length, 0);
+            if self.negative {
+                let mut carry = true;
+                for limb in limbs.iter_mut() {
+                    *limb = !*limb;
+                    if carry {
+                        let (sum, overflow) = limb.overflowing_add(1);
+                        *limb = sum;
+                        carry = overflow;
+                    }
+                }
+            }
+            limbs
+        }
+
+        fn from_twos_complement(mut limbs: Vec<u32>) -> Self {
+            let negative = limbs.last().is_some_and(|x| x >> 31 == 1);
+            if negative {
+                let mut borrow = true;
+                for limb in limbs.iter_mut() {
+                    if borrow {
+                        let (difference, overflow) = limb.overflowing_sub(1);
+                        *limb = difference;
+                        borrow = overflow;
+                    }
+                    *limb = !*limb;
+                }
+            }
+            BigInt::new(negative, limbs)
+        }
+
+        fn bitwise(&self, other: &BigInt, operation: fn(u32, u32) -> u32) -> Self {
+            let length = self.magnitude.len().max(other.magnitude.len()) + 1;
+            let a = self.to_twos_complement(length);
+            let b = other.to_twos_complement(length);
+            BigInt::from_twos_complement(
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| operation(*x, *y))
+                    .collect(),
+            )
+        }
+
+        pub fn and(&self, other: &BigInt) -> Self {
+            self.bitwise(other, |x, y| x & y)
+        }
+
+        pub fn or(&self, other: &BigInt) -> Self {
+            self.bitwise(other, |x, y| x | y)
+        }
+
+        pub fn xor(&self, other: &BigInt) -> Self {
+            self.bitwise(other, |x, y| x ^ y)
+        }
+
+        /// -n - 1, which flips every bit of the two's complement form
+        pub fn not(&self) -> Self {
+            self.negate().substract(&BigInt::from_i128(1))
+        }
+
+        /// n * 2^k, rounding toward negative infinity when k < 0.
+        pub fn shift(&self, k: i64) -> Self {
+            if k >= 0 {
+                let limbs = (k / 32) as usize;
+                let bits = (k % 32) as u32;
+                let mut magnitude = vec![0u32; limbs];
+                let mut carry = 0u32;
+                for limb in self.magnitude.iter() {
+                    magnitude.push((limb << bits) | carry);
+                    carry = if bits == 0 { 0 } else { limb >> (32 - bits) };
+                }
+                magnitude.push(carry);
+                BigInt::new(self.negative, magnitude)
+            } else if self.negative {
+                // floor(n / 2^k) = -(floor((|n| - 1) / 2^k) + 1)
+                self.not().shift(k).not()
+            } else {
+                let limbs = (-k / 32) as usize;
+                let bits = (-k % 32) as u32;
+                if limbs >= self.magnitude.len() {
+                    return BigInt::from_i128(0);
+                }
+                let rest = &self.magnitude[limbs..];
+                let mut magnitude = vec![];
+                for i in 0..rest.len() {
+                    let high = if bits == 0 {
+                        0
+                    } else {
+                        rest.get(i + 1).map_or(0, |x| x << (32 - bits))
+                    };
+                    magnitude.push((rest[i] >> bits) | high);
+                }
+                BigInt::new(false, magnitude)
+            }
+        }
+
+        // the magnitude of n, or of -n - 1 when n is negative, whose
+        // bits are the bits of n or their complement
+        fn nonnegative_bits(&self) -> Vec<u32> {
+            if self.negative {
+                self.not().magnitude
+            } else {
+                self.magnitude.clone()
+            }
+        }
+
+        /// The number of 1 bits of a nonnegative n, or of 0 bits of a
+        /// negative one.
+        pub fn bit_count(&self) -> u64 {
+            self.nonnegative_bits()
+                .iter()
+                .map(|x| x.count_ones() as u64)
+                .sum()
+        }
+
+        /// The number of bits needed to represent n, not counting the
+        /// sign.
+        pub fn integer_length(&self) -> u64 {
+            let bits = self.nonnegative_bits();
+            match bits.last() {
+                Some(x) => (bits.len() as u64 - 1) * 32 + (32 - x.leading_zeros() as u64),
+                None => 0,
+            }
+        }
+
+        pub fn is_bit_set(&self, index: u64) -> bool {
+            let limb = self.nonnegative_bits().get((index / 32) as usize).copied();
+            let bit = limb.is_some_and(|x| (x >> (index % 32)) & 1 == 1);
+            bit != self.negative
+        }
+    }
+
+    impl PartialOrd for BigInt {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for BigInt {
+        fn cmp(&self, other: &Self) -> Ordering {
+            match (self.negative, other.negative) {
+                (false, true) => Ordering::Greater,
+                (true, false) => Ordering::Less,
+                (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+                (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude),
+            }
+        }
+    }
+
+    impl fmt::Display for BigInt {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            if self.magnitude.is_empty() {
+                return write!(f, "0");
+            }
+            // nine decimal digits at a time
+            let mut chunks = vec![];
+            let mut rest = self.magnitude.clone();
+            while !rest.is_empty() {
+                let (quotient, remainder) = divide_magnitude(&rest, 1_000_000_000);
+                chunks.push(remainder);
+                rest = quotient;
+            }
+            let mut s = String::new();
+            if self.negative {
+                s.push('-');
+            }
+            s.push_str(&chunks.pop().unwrap().to_string());
+            while let Some(chunk) = chunks.pop() {
+                s.push_str(&format!("{:09}", chunk));
+            }
+            write!(f, "{}", s)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::bigint::BigInt;
+
+    #[test]
+    fn test_arithmetic_and_printing() {
+        let big = BigInt::parse("123456789012345678901234567890").unwrap();
+        assert_eq!(big.to_string(), "123456789012345678901234567890");
+        assert_eq!(
+            big.multiply(&big.negate()).to_string(),
+            "-15241578753238836750495351562536198787501905199875019052100"
+        );
+        assert_eq!(big.substract(&big).to_string(), "0");
+        assert_eq!(
+            BigInt::from_i128(i64::MAX as i128)
+                .add(&BigInt::from_i128(1))
+                .to_i64(),
+            None
+        );
+        assert_eq!(BigInt::from_i128(-5).to_i64(), Some(-5));
+        assert!(BigInt::from_i128(-5) < BigInt::from_i128(3));
+        let (q, r) = big.divide(&BigInt::parse("-98765432109876").unwrap());
+        assert_eq!(q.to_string(), "-1249999988609381");
+        assert_eq!(r.to_string(), "86435198221134");
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let a = BigInt::from_i128(-12);
+        let b = BigInt::from_i128(10);
+        assert_eq!(a.and(&b), BigInt::from_i128(-12 & 10));
+        assert_eq!(a.or(&b), BigInt::from_i128(-12 | 10));
+        assert_eq!(a.xor(&b), BigInt::from_i128(-12 ^ 10));
+        assert_eq!(a.not(), BigInt::from_i128(11));
+        assert_eq!(a.shift(-3), BigInt::from_i128(-2));
+        assert_eq!(b.shift(70), BigInt::from_i128(10 << 70));
+        assert_eq!(a.bit_count(), 3);
+        assert_eq!(b.integer_length(), 4);
+        assert!(a.is_bit_set(2));
+        assert!(!a.is_bit_set(0));
+        assert!(a.is_bit_set(100));
+    }
+}
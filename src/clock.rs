@@ -0,0 +1,77 @@
+#[allow(clippy::module_inception)]
+pub mod clock {
+    use std::sync::OnceLock;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    /* The standard library has no processor time, so on Unix it comes
+     * from clock() of the C library, which is declared here by hand.
+     * clock_t is a long on the Unix systems Rust supports, and POSIX
+     * fixes CLOCKS_PER_SEC at one million. */
+    #[cfg(unix)]
+    extern "C" {
+        fn clock() -> std::os::raw::c_long;
+    }
+
+    #[cfg(unix)]
+    const CLOCKS_PER_SEC: f64 = 1_000_000.0;
+
+    /// Jiffies are microseconds since the clock was first read.
+    pub const JIFFIES_PER_SECOND: i64 = 1_000_000;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    /// The processor time used by the interpreter, in seconds.
+    #[cfg(unix)]
+    #[allow(dead_code)]
+    pub fn cpu_time() -> f64 {
+        unsafe { clock() as f64 / CLOCKS_PER_SEC }
+    }
+
+    /// Elsewhere there is no processor time, so this is the wall clock
+    /// time since the clock was first read. Windows' clock() would be
+    /// wall clock time too.
+    #[cfg(not(unix))]
+    #[allow(dead_code)]
+    pub fn cpu_time() -> f64 {
+        START.get_or_init(Instant::now).elapsed().as_secs_f64()
+    }
+
+    /// Seconds since the Unix epoch.
+    #[allow(dead_code)]
+    pub fn current_second() -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64())
+    }
+
+    #[allow(dead_code)]
+    pub fn current_jiffy() -> i64 {
+        START.get_or_init(Instant::now).elapsed().as_micros() as i64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::clock::{cpu_time, current_jiffy, current_second};
+    use std::hint::black_box;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_clocks_advance() {
+        let jiffy = current_jiffy();
+        let cpu = cpu_time();
+        // busy until both clocks have ticked, a fixed amount of work may
+        // fit in one tick of a coarse clock
+        let start = Instant::now();
+        while (current_jiffy() == jiffy || cpu_time() == cpu)
+            && start.elapsed() < Duration::from_secs(10)
+        {
+            for i in 0..10_000u64 {
+                black_box(i * i);
+            }
+        }
+        assert!(current_jiffy() > jiffy);
+        assert!(cpu_time() > cpu);
+        assert!(current_second() > 1.6e9);
+    }
+}
@@ -1,30 +1,76 @@
 #![allow(unused_variables)]
 
+#[allow(clippy::module_inception)]
 pub mod core_of_interpreter {
+    use crate::bigint::bigint::BigInt;
+    use crate::clock::clock::{cpu_time, current_jiffy, current_second, JIFFIES_PER_SECOND};
     use crate::environment::env::*;
-    use crate::represent::represent::{cadr, car, first_operand, no_operands, rest_operands};
+    use crate::number::number::{
+        abs, add, angle, arithmetic_shift, atan2, bit_count, bitwise_and, bitwise_not, bitwise_or,
+        bitwise_xor, ceiling, compare, denominator, divide, exact_integer_sqrt, exact_to_inexact,
+        exp, expt, floor, imag_part, inexact_to_exact, integer_division, integer_gcd, integer_lcm,
+        integer_length, is_bit_set, is_exact, is_numerically_equal, is_odd, log, magnitude,
+        make_polar, make_rectangular, multiply, negate, numerator, rationalize, real_part, round,
+        sqrt, substract, trigonometric, truncate,
+    };
+    use crate::parameter::parameter::{Parameter, ParameterizeGuard};
+    use crate::port::port::{
+        current_error_port, current_input_port, current_output_port, input_port, make_port,
+        open_output_file, output_port, FileInputPort, Port, StringInputPort, StringOutputPort,
+    };
+    use crate::process::process::{
+        command_line, emergency_exit, exit, get_environment_variable, get_environment_variables,
+    };
+    use crate::random::random::{random, random_seed};
+    use crate::record::record::{apply_record_procedure, Record, RecordProcedure, RecordType};
+    use crate::represent::represent::{
+        cadr, car, cddr, cdr, first_operand, no_operands, rest_operands, Procedure,
+    };
+    use crate::symbol::symbol::{Symbol, APPLY};
     use crate::{
-        display::display::pretty_print,
+        display::display::exp_to_string,
+        library::library::{
+            current_library, eval_import, eval_library_definition, global_environment,
+            in_captured_environment, library_search_path, set_global_environment, LibraryGuard,
+        },
+        parser::parser::{read_forms_from_file, read_from_port},
         represent::represent::{
             assignment_value, assignment_variable, begin_actions, caar, cdar, definition_value,
             definition_variable, first_exp, if_alternative, if_consequent, if_predicate,
-            is_application, is_assignment, is_begin, is_compound_procedure, is_definiton, is_if,
-            is_lambda, is_last_exp, is_number_combination, is_primitive_procedure, lambda_body,
-            lambda_parameters, make_procedure, operands, operator, procedure_body,
-            procedure_parameters, rest_exps,
+            include_files, is_application, is_assignment, is_begin, is_compound_procedure,
+            is_definiton, is_if, is_import, is_include, is_lambda, is_last_exp,
+            is_library_definition, is_parameterize, is_primitive_procedure,
+            is_record_type_definition, is_the_environment, is_time, lambda_body, lambda_parameters,
+            make_begin, make_library_procedure, make_procedure, operands, operator,
+            parameterize_bindings, parameterize_body, procedure_body, procedure_library,
+            procedure_parameters, record_constructor, record_fields, record_predicate,
+            record_type_name, rest_exps, text_of_quotation, time_expression,
+        },
+        scheme_list,
+        tool::tools::{
+            append, last_pair, list_length, list_tail, list_to_vec, reverse, scheme_cons,
+            vec_to_list,
         },
-        tool::tools::{list_length, scheme_cons},
     };
+    use std::cell::RefCell;
+    use std::cmp::Ordering;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::sync::LazyLock;
+    use std::time::Instant;
 
     pub static mut ENV: Env = Env(Exp::List(Pair::Nil));
 
+    type PrimitiveProcedure = fn(Exp) -> Exp;
+
     #[allow(dead_code)]
     #[derive(Debug, Clone)]
     pub enum Pair {
-        Cons(Box<Exp>, Box<Pair>),
+        Cons(Rc<Exp>, Rc<Pair>),
         Nil,
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     impl PartialEq for Pair {
         fn eq(&self, other: &Self) -> bool {
             match self {
@@ -54,15 +100,32 @@ pub mod core_of_interpreter {
     #[allow(dead_code)]
     #[derive(Debug, Clone)]
     pub enum Exp {
-        FloatNumber(f32),
-        Integer(i32),
+        FloatNumber(f64),
+        Integer(i64),
+        BigInteger(Rc<BigInt>),
+        Rational(i64, i64),
+        BigRational(Rc<BigInt>, Rc<BigInt>),
+        Complex(Box<Exp>, Box<Exp>),
         List(Pair),
-        Symbol(String),
-        Quote(String),
+        Symbol(Symbol),
         SchemeString(String),
         Bool(bool),
+        Character(char),
+        Primitive(Symbol),
+        Procedure(Rc<Procedure>),
+        Vector(Rc<Vec<Exp>>),
+        Record(Rc<Record>),
+        RecordType(Rc<RecordType>),
+        RecordProcedure(Rc<RecordProcedure>),
+        Parameter(Rc<Parameter>),
+        Bytevector(Rc<RefCell<Vec<u8>>>),
+        Port(Rc<RefCell<dyn Port>>),
+        Eof,
+        Unspecified,
+        Environment(Environment),
     }
 
+    #[allow(clippy::match_like_matches_macro)]
     impl PartialEq for Exp {
         fn eq(&self, other: &Self) -> bool {
             match self {
@@ -80,22 +143,43 @@ pub mod core_of_interpreter {
                     _ => false,
                 },
 
-                Exp::List(x) => match other {
-                    Exp::List(y) => {
+                Exp::BigInteger(x) => match other {
+                    Exp::BigInteger(y) => {
                         x == y
                     },
                     _ => false,
                 },
 
-                Exp::Symbol(x) => match other {
-                    Exp::Symbol(y) => {
+                Exp::Rational(x, y) => match other {
+                    Exp::Rational(x1, y1) => {
+                        x == x1 && y == y1
+                    },
+                    _ => false,
+                },
+
+                Exp::BigRational(x, y) => match other {
+                    Exp::BigRational(x1, y1) => {
+                        x == x1 && y == y1
+                    },
+                    _ => false,
+                },
+
+                Exp::Complex(x, y) => match other {
+                    Exp::Complex(x1, y1) => {
+                        x == x1 && y == y1
+                    },
+                    _ => false,
+                },
+
+                Exp::List(x) => match other {
+                    Exp::List(y) => {
                         x == y
                     },
                     _ => false,
                 },
 
-                Exp::Quote(x) => match other {
-                    Exp::Quote(y) => {
+                Exp::Symbol(x) => match other {
+                    Exp::Symbol(y) => {
                         x == y
                     },
                     _ => false,
@@ -114,432 +198,1715 @@ pub mod core_of_interpreter {
                     },
                     _ => false,
                 },
+
+                Exp::Character(x) => match other {
+                    Exp::Character(y) => {
+                        x == y
+                    },
+                    _ => false,
+                },
+
+                Exp::Primitive(x) => match other {
+                    Exp::Primitive(y) => {
+                        x == y
+                    },
+                    _ => false,
+                },
+
+                Exp::Vector(x) => match other {
+                    Exp::Vector(y) => {
+                        x == y
+                    },
+                    _ => false,
+                },
+
+                Exp::Record(x) => match other {
+                    Exp::Record(y) => {
+                        Rc::ptr_eq(x, y)
+                    },
+                    _ => false,
+                },
+
+                Exp::RecordType(x) => match other {
+                    Exp::RecordType(y) => {
+                        Rc::ptr_eq(x, y)
+                    },
+                    _ => false,
+                },
+
+                Exp::RecordProcedure(x) => match other {
+                    Exp::RecordProcedure(y) => {
+                        Rc::ptr_eq(x, y)
+                    },
+                    _ => false,
+                },
+
+                Exp::Procedure(x) => match other {
+                    Exp::Procedure(y) => {
+                        Rc::ptr_eq(x, y)
+                    },
+                    _ => false,
+                },
+
+                Exp::Parameter(x) => match other {
+                    Exp::Parameter(y) => {
+                        Rc::ptr_eq(x, y)
+                    },
+                    _ => false,
+                },
+
+                Exp::Bytevector(x) => match other {
+                    Exp::Bytevector(y) => {
+                        x == y
+                    },
+                    _ => false,
+                },
+
+                Exp::Port(x) => match other {
+                    Exp::Port(y) => {
+                        Rc::ptr_eq(x, y)
+                    },
+                    _ => false,
+                },
+
+                Exp::Eof => match other {
+                    Exp::Eof => true,
+                    _ => false,
+                },
+
+                Exp::Unspecified => match other {
+                    Exp::Unspecified => true,
+                    _ => false,
+                },
+
+                Exp::Environment(x) => match other {
+                    Exp::Environment(y) => {
+                        x == y
+                    },
+                    _ => false,
+                },
             }
         }
     }
 
+    // the global environment with every primitive procedure bound
+    #[allow(dead_code)]
+    pub fn setup_environment() -> Env {
+        let mut variables = Exp::List(Pair::Nil);
+        let mut values = Exp::List(Pair::Nil);
+        for (name, _) in PRIMITIVE_PROCEDURES.iter().rev() {
+            let name = Symbol::new(name);
+            variables = scheme_cons(Exp::Symbol(name), variables);
+            values = scheme_cons(Exp::Primitive(name), values);
+        }
+        let ports = [
+            ("current-input-port", current_input_port()),
+            ("current-output-port", current_output_port()),
+            ("current-error-port", current_error_port()),
+            ("library-search-path", library_search_path()),
+        ];
+        for (name, port) in ports {
+            variables = scheme_cons(Exp::Symbol(Symbol::new(name)), variables);
+            values = scheme_cons(Exp::Parameter(port), values);
+        }
+        variables = scheme_cons(
+            Exp::Symbol(Symbol::new("system-global-environment")),
+            variables,
+        );
+        values = scheme_cons(Exp::Environment(Environment::Global), values);
+        extend_environment(variables, values, Env(Exp::List(Pair::Nil)))
+    }
+
     /* core function of the Scheme interpreter */
+    // Expressions in tail position, the branches of if, the last
+    // expression of a body and compound procedure calls, are evaluated by
+    // looping instead of recursing, so tail calls do not grow the stack.
     #[allow(dead_code)]
-    pub fn eval(exp: Exp, env: Env) -> Result<Option<Exp>, &'static str> {
-        if exp.is_self_evaluating() {
-            Ok(Some(exp))
-        } else if is_number_combination(exp.clone()) {
-            Ok(Some(exp))
-        } else if exp.is_primitive_procedure() {
-            Ok(Some(scheme_cons(Exp::Symbol("primitive".to_string()), exp)))
-        } else if exp.is_variable() {
-            Ok(Some(lookup_variable_value(exp, env)))
-        } else if exp.is_quoted() {
-            Ok(Some(exp))
-        } else if is_assignment(exp.clone()) {
-            Ok(eval_assignment(exp, env))
-        } else if is_definiton(exp.clone()) {
-            Ok(eval_definition(exp, env))
-        } else if is_if(exp.clone()) {
-            Ok(eval_if(exp, env))
-        } else if is_lambda(exp.clone()) {
-            Ok(Some(make_procedure(
-                lambda_parameters(exp.clone()),
-                lambda_body(exp),
-            )))
-        } else if is_begin(exp.clone()) {
-            Ok(eval_sequence(begin_actions(exp), env))
-        } else if is_application(exp.clone()) {
-            apply(
-                eval(operator(exp.clone()), env.clone()).unwrap().unwrap(),
-                list_of_values(operands(exp), env),
-            )
-        } else {
-            Err("unknow expression, type: EVAL")
+    pub fn eval(exp: Exp, env: Env) -> Result<Exp, &'static str> {
+        let mut exp = exp;
+        let mut env = env;
+        loop {
+            if exp.is_self_evaluating() {
+                return Ok(exp);
+            } else if exp.is_variable() {
+                return Ok(lookup_variable_value(exp, env));
+            } else if exp.is_quoted() {
+                return Ok(text_of_quotation(exp));
+            } else if is_assignment(exp.clone()) {
+                return eval_assignment(exp, env);
+            } else if is_definiton(exp.clone()) {
+                return eval_definition(exp, env);
+            } else if is_record_type_definition(exp.clone()) {
+                return Ok(eval_record_type_definition(exp, env));
+            } else if is_parameterize(exp.clone()) {
+                return eval_parameterize(exp, env);
+            } else if is_the_environment(exp.clone()) {
+                return Ok(Exp::Environment(Environment::Captured(Rc::new(
+                    RefCell::new(env),
+                ))));
+            } else if is_time(exp.clone()) {
+                return eval_time(exp, env);
+            } else if is_library_definition(exp.clone()) {
+                return eval_library_definition(exp);
+            } else if is_import(exp.clone()) {
+                return Ok(eval_import(exp));
+            } else if is_include(exp.clone()) {
+                let forms = read_included_forms(include_files(exp));
+                if no_operands(forms.clone()) {
+                    return Ok(Exp::Unspecified);
+                }
+                exp = make_begin(forms);
+            } else if is_if(exp.clone()) {
+                let predicate = eval(if_predicate(exp.clone()), env.clone())?;
+                exp = if predicate.is_true() {
+                    if_consequent(exp)
+                } else {
+                    if_alternative(exp)
+                };
+            } else if is_lambda(exp.clone()) {
+                let parameters = lambda_parameters(exp.clone());
+                return Ok(match current_library() {
+                    Some(x) => make_library_procedure(parameters, lambda_body(exp), x),
+                    None => make_procedure(parameters, lambda_body(exp)),
+                });
+            } else if is_begin(exp.clone()) {
+                let (last, last_env) = eval_all_but_last(begin_actions(exp), env)?;
+                exp = last;
+                env = last_env;
+            } else if is_application(exp.clone()) {
+                let mut p = eval(operator(exp.clone()), env.clone())?;
+                let mut args = list_of_values(operands(exp), env)?;
+                // (apply f a '(b c)) is a tail call of (f a b c)
+                while p == Exp::Primitive(APPLY) {
+                    let (f, spread_args) = spread_arguments(args);
+                    p = f;
+                    args = spread_args;
+                }
+                // a call into another library, or out of the environment
+                // eval runs in, goes through apply, which switches global
+                // environments
+                if is_compound_procedure(p.clone())
+                    && procedure_library(p.clone()) == current_library()
+                    && !in_captured_environment()
+                {
+                    let new_env = extend_environment(
+                        procedure_parameters(p.clone()),
+                        args,
+                        global_environment(),
+                    );
+                    let (last, last_env) = eval_all_but_last(procedure_body(p), new_env)?;
+                    exp = last;
+                    env = last_env;
+                } else {
+                    return apply(p, args);
+                }
+            } else {
+                return Err("unknow expression, type: EVAL");
+            }
         }
     }
 
+    /// Call a procedure on a list of evaluated arguments. Primitives such
+    /// as map use it to call back the procedures they are given.
     #[allow(dead_code)]
-    fn apply(p: Exp, args: Exp) -> Result<Option<Exp>, &'static str> {
+    pub fn apply(p: Exp, args: Exp) -> Result<Exp, &'static str> {
         if is_primitive_procedure(p.clone()) {
             Ok(apply_primitive_procedure(p, args))
-        } else if is_compound_procedure(p.clone()) {
-            unsafe {
-                Ok(eval_sequence(
-                    procedure_body(p.clone()),
-                    extend_environment(procedure_parameters(p.clone()), args, ENV.clone()),
-                ))
+        } else if let Exp::RecordProcedure(x) = p {
+            Ok(apply_record_procedure(&x, args))
+        } else if let Exp::Parameter(x) = p {
+            if no_operands(args) {
+                Ok(x.value.borrow().clone())
+            } else {
+                Err("a parameter takes no arguments: APPLY")
             }
+        } else if is_compound_procedure(p.clone()) {
+            let _guard = LibraryGuard::new(procedure_library(p.clone()));
+            eval_sequence(
+                procedure_body(p.clone()),
+                extend_environment(procedure_parameters(p.clone()), args, global_environment()),
+            )
         } else {
             Err("unknow procedure type: APPLY")
         }
     }
 
-    #[allow(dead_code)]
-    fn eval_sequence(exps: Exp, env: Env) -> Option<Exp> {
-        if is_last_exp(exps.clone()) {
-            eval(first_exp(exps), env).unwrap()
-        } else {
-            let temp = eval(first_exp(exps.clone()), env.clone()).unwrap();
-            match temp {
-                None => unsafe { eval_sequence(rest_exps(exps), ENV.clone()) },
-                Some(x) => eval_sequence(rest_exps(exps), env.clone()),
+    // Evaluate every expression of a sequence but the last one, which is
+    // returned with the environment it should be evaluated in. A definition
+    // changes the environment the rest of the sequence sees.
+    fn eval_all_but_last(exps: Exp, env: Env) -> Result<(Exp, Env), &'static str> {
+        let mut exps = exps;
+        let mut env = env;
+        while !is_last_exp(exps.clone()) {
+            let exp = first_exp(exps.clone());
+            let changes_env = is_definiton(exp.clone())
+                || is_assignment(exp.clone())
+                || is_record_type_definition(exp.clone())
+                || is_include(exp.clone())
+                || is_import(exp.clone());
+            eval(exp, env.clone())?;
+            if changes_env {
+                env = global_environment();
             }
+            exps = rest_exps(exps);
         }
+        Ok((first_exp(exps), env))
+    }
+
+    #[allow(dead_code)]
+    fn eval_sequence(exps: Exp, env: Env) -> Result<Exp, &'static str> {
+        let (last, last_env) = eval_all_but_last(exps, env)?;
+        eval(last, last_env)
     }
 
     #[allow(dead_code)]
-    fn list_of_values(exps: Exp, env: Env) -> Exp {
+    fn list_of_values(exps: Exp, env: Env) -> Result<Exp, &'static str> {
         if no_operands(exps.clone()) {
-            Exp::List(Pair::Nil)
+            Ok(Exp::List(Pair::Nil))
         } else {
-            scheme_cons(
-                eval(first_operand(exps.clone()), env.clone())
-                    .unwrap()
-                    .unwrap(),
-                list_of_values(rest_operands(exps), env.clone()),
-            )
+            Ok(scheme_cons(
+                eval(first_operand(exps.clone()), env.clone())?,
+                list_of_values(rest_operands(exps), env.clone())?,
+            ))
         }
     }
 
     #[allow(dead_code)]
-    fn eval_if(exp: Exp, env: Env) -> Option<Exp> {
-        if eval(if_predicate(exp.clone()), env.clone())
-            .unwrap()
-            .unwrap()
-            == Exp::Bool(true)
-        {
-            eval(if_consequent(exp), env).unwrap()
-        } else {
-            eval(if_alternative(exp), env).unwrap()
+    fn eval_assignment(exp: Exp, env: Env) -> Result<Exp, &'static str> {
+        let temp = set_variable_value(
+            assignment_variable(exp.clone()),
+            eval(assignment_value(exp), env.clone())?,
+            env.clone(),
+        );
+        set_global_environment(temp);
+        Ok(Exp::Unspecified)
+    }
+
+    #[allow(dead_code)]
+    fn eval_definition(exp: Exp, env: Env) -> Result<Exp, &'static str> {
+        let temp = define_variable(
+            definition_variable(exp.clone()),
+            eval(definition_value(exp), env.clone())?,
+            env.clone(),
+        );
+        set_global_environment(temp);
+        Ok(Exp::Unspecified)
+    }
+
+    // binds the record type, its constructor, predicate, accessors and
+    // modifiers in the environment
+    #[allow(dead_code)]
+    fn eval_record_type_definition(exp: Exp, env: Env) -> Exp {
+        let name = match record_type_name(exp.clone()) {
+            Exp::Symbol(x) => x,
+            _ => panic!("record type name must be a symbol!"),
+        };
+        let mut fields = vec![];
+        let mut rest = record_fields(exp.clone());
+        while rest.is_pair() {
+            match car(rest.clone()).unwrap() {
+                Exp::Symbol(x) => fields.push(x),
+                x if x.is_pair() => match car(x).unwrap() {
+                    Exp::Symbol(x) => fields.push(x),
+                    _ => panic!("record field must be a symbol!"),
+                },
+                _ => panic!("bad record field!"),
+            }
+            rest = cdr(rest).unwrap();
+        }
+        let record_type = Rc::new(RecordType { name, fields });
+        let mut bindings = vec![(Exp::Symbol(name), Exp::RecordType(record_type.clone()))];
+        // (make-point x y), or make-point taking every field
+        match record_constructor(exp.clone()) {
+            Exp::Symbol(x) => {
+                let indexes = (0..record_type.fields.len()).collect();
+                let p = RecordProcedure::Constructor(record_type.clone(), indexes);
+                bindings.push((Exp::Symbol(x), Exp::RecordProcedure(Rc::new(p))));
+            }
+            x if x.is_pair() => {
+                let mut indexes = vec![];
+                let mut rest = cdr(x.clone()).unwrap();
+                while rest.is_pair() {
+                    match car(rest.clone()).unwrap() {
+                        Exp::Symbol(field) => indexes.push(record_type.field_index(field)),
+                        _ => panic!("record field must be a symbol!"),
+                    }
+                    rest = cdr(rest).unwrap();
+                }
+                let p = RecordProcedure::Constructor(record_type.clone(), indexes);
+                bindings.push((car(x).unwrap(), Exp::RecordProcedure(Rc::new(p))));
+            }
+            _ => {}
         }
+        let p = RecordProcedure::Predicate(record_type.clone());
+        bindings.push((
+            record_predicate(exp.clone()),
+            Exp::RecordProcedure(Rc::new(p)),
+        ));
+        // (x point-x set-point-x!)
+        let mut rest = record_fields(exp);
+        while rest.is_pair() {
+            let spec = car(rest.clone()).unwrap();
+            if spec.is_pair() {
+                let i = record_type.field_index(match car(spec.clone()).unwrap() {
+                    Exp::Symbol(x) => x,
+                    _ => panic!("record field must be a symbol!"),
+                });
+                let mut names = cdr(spec).unwrap();
+                if names.is_pair() {
+                    let p = RecordProcedure::Accessor(record_type.clone(), i);
+                    bindings.push((
+                        car(names.clone()).unwrap(),
+                        Exp::RecordProcedure(Rc::new(p)),
+                    ));
+                    names = cdr(names).unwrap();
+                }
+                if names.is_pair() {
+                    let p = RecordProcedure::Modifier(record_type.clone(), i);
+                    bindings.push((car(names).unwrap(), Exp::RecordProcedure(Rc::new(p))));
+                }
+            }
+            rest = cdr(rest).unwrap();
+        }
+        let mut temp = env;
+        for (var, val) in bindings {
+            temp = define_variable(var, val, temp);
+        }
+        set_global_environment(temp);
+        Exp::Unspecified
     }
 
+    /// Evaluates the forms of a file one by one in the global environment.
     #[allow(dead_code)]
-    fn eval_assignment(exp: Exp, env: Env) -> Option<Exp> {
-        unsafe {
-            let temp = set_variable_value(
-                assignment_variable(exp.clone()),
-                eval(assignment_value(exp), env.clone()).unwrap().unwrap(),
-                env.clone(),
-            );
-            ENV = temp;
+    pub fn load(name: &str) {
+        load_file(&resolve_file_name(name));
+    }
+
+    /// Evaluates the forms of a file, the names it loads or includes are
+    /// relative to its directory.
+    #[allow(dead_code)]
+    pub fn load_file(path: &Path) {
+        let mut forms = read_forms_from_file(&path.to_string_lossy());
+        let _guard = LoadDirectoryGuard::new(path.parent().map(Path::to_path_buf));
+        while forms.is_pair() {
+            eval(car(forms.clone()).unwrap(), global_environment()).unwrap();
+            forms = cdr(forms).unwrap();
+        }
+    }
+
+    thread_local! {
+        // the directory of the file being loaded, None at the top level
+        static LOAD_DIRECTORY: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+    }
+
+    // makes dir the load directory, the previous one comes back when the
+    // guard is dropped
+    struct LoadDirectoryGuard(Option<PathBuf>);
+
+    impl LoadDirectoryGuard {
+        fn new(dir: Option<PathBuf>) -> Self {
+            LoadDirectoryGuard(LOAD_DIRECTORY.with(|x| x.replace(dir)))
         }
-        None
     }
 
+    impl Drop for LoadDirectoryGuard {
+        fn drop(&mut self) {
+            LOAD_DIRECTORY.with(|x| x.replace(self.0.take()));
+        }
+    }
+
+    /// A relative name given to load or include is taken from the
+    /// directory of the file being loaded, or the current directory at
+    /// the top level.
     #[allow(dead_code)]
-    fn eval_definition(exp: Exp, env: Env) -> Option<Exp> {
-        unsafe {
-            let temp = define_variable(
-                definition_variable(exp.clone()),
-                eval(definition_value(exp), env.clone()).unwrap().unwrap(),
-                env.clone(),
-            );
-            ENV = temp;
-        }
-        None
-    }
-
-    fn apply_primitive_procedure(p: Exp, args: Exp) -> Option<Exp> {
-        if let Exp::Symbol(x) = cadr(p).unwrap() {
-            match x {
-                t if t == "*".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        match lhs {
-                            Exp::Integer(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let r = x * y;
-                                    Some(Exp::Integer(r))
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let r = x as f32 * y;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                _ => panic!("wrong type for multiply!"),
-                            },
-                            Exp::FloatNumber(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let r = x * y as f32;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let r = x * y;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                _ => panic!("wrong type for multiply!"),
-                            },
-                            _ => panic!("wrong type for multiply!"),
-                        }
-                    } else {
-                        panic!("wrong number of args!");
-                    }
+    pub fn resolve_file_name(name: &str) -> PathBuf {
+        match LOAD_DIRECTORY.with(|x| x.borrow().clone()) {
+            // joining keeps an absolute name as it is
+            Some(dir) => dir.join(name),
+            None => PathBuf::from(name),
+        }
+    }
+
+    // the forms of every file named by an include, in order
+    fn read_included_forms(files: Exp) -> Exp {
+        let mut forms = Exp::List(Pair::Nil);
+        let mut rest = files;
+        while rest.is_pair() {
+            match car(rest.clone()).unwrap() {
+                Exp::SchemeString(x) => {
+                    let path = resolve_file_name(&x);
+                    forms = append(forms, read_forms_from_file(&path.to_string_lossy()))
                 }
-                t if t == "/".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        match rhs {
-                            Exp::Integer(x) => {
-                                if x == 0 {
-                                    panic!("divide by zero!");
-                                } else {
-                                    match lhs {
-                                        Exp::Integer(y) => {
-                                            Some(Exp::FloatNumber((y as f32) / (x as f32)))
-                                        }
-                                        Exp::FloatNumber(y) => {
-                                            Some(Exp::FloatNumber(y / (x as f32)))
-                                        }
-                                        _ => panic!("wrong type for division!"),
-                                    }
-                                }
-                            }
-                            Exp::FloatNumber(x) => {
-                                if x == 0.0 {
-                                    panic!("divide by zero!");
-                                } else {
-                                    match lhs {
-                                        Exp::Integer(y) => Some(Exp::FloatNumber((y as f32) / x)),
-                                        Exp::FloatNumber(y) => Some(Exp::FloatNumber(y / x)),
-                                        _ => panic!("wrong type for division!"),
-                                    }
-                                }
-                            }
-                            _ => panic!("wrong type for division!"),
-                        }
-                    } else {
-                        panic!("wrong number of args!");
-                    }
+                _ => panic!("include needs file names!"),
+            }
+            rest = cdr(rest).unwrap();
+        }
+        forms
+    }
+
+    // a value given to a parameter goes through its converter
+    fn convert_parameter_value(p: &Parameter, value: Exp) -> Exp {
+        match &p.converter {
+            Some(converter) => call_procedure(converter, scheme_list!(value)),
+            None => value,
+        }
+    }
+
+    // (time expr) reports how long expr took on the current output port
+    #[allow(dead_code)]
+    fn eval_time(exp: Exp, env: Env) -> Result<Exp, &'static str> {
+        let real = Instant::now();
+        let cpu = cpu_time();
+        let value = eval(time_expression(exp), env)?;
+        let report = format!(
+            "cpu time: {:.3}s, real time: {:.3}s\n",
+            cpu_time() - cpu,
+            real.elapsed().as_secs_f64()
+        );
+        output_port(None).borrow_mut().write_str(&report);
+        Ok(value)
+    }
+
+    // every parameter and value is evaluated before any is rebound, the
+    // old values come back when the guard is dropped
+    #[allow(dead_code)]
+    fn eval_parameterize(exp: Exp, env: Env) -> Result<Exp, &'static str> {
+        let mut bindings = vec![];
+        let mut rest = parameterize_bindings(exp.clone());
+        while rest.is_pair() {
+            let binding = car(rest.clone()).unwrap();
+            let p = match eval(car(binding.clone()).unwrap(), env.clone())? {
+                Exp::Parameter(x) => x,
+                _ => panic!("not a parameter: parameterize"),
+            };
+            let value = eval(cadr(binding).unwrap(), env.clone())?;
+            let value = convert_parameter_value(&p, value);
+            bindings.push((p, value));
+            rest = cdr(rest).unwrap();
+        }
+        let mut guard = ParameterizeGuard::new();
+        for (p, value) in bindings {
+            guard.bind(p, value);
+        }
+        eval_sequence(parameterize_body(exp), env)
+    }
+
+    // the k-th argument, if it is given
+    fn optional_argument(args: Exp, k: i64) -> Option<Exp> {
+        let rest = list_tail(args, k);
+        if rest.is_pair() {
+            Some(car(rest).unwrap())
+        } else {
+            None
+        }
+    }
+
+    // (with-output-to-string thunk) and (call-with-output-string proc)
+    // both return what was written to a fresh string port
+    fn output_to_string(f: Exp, as_current_port: bool) -> Exp {
+        let port = make_port(StringOutputPort::default());
+        if as_current_port {
+            let mut guard = ParameterizeGuard::new();
+            guard.bind(current_output_port(), port.clone());
+            call_procedure(&f, Exp::List(Pair::Nil));
+        } else {
+            call_procedure(&f, scheme_list!(port.clone()));
+        }
+        match port {
+            Exp::Port(x) => Exp::SchemeString(x.borrow().output_string().unwrap()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn file_name(x: Exp) -> String {
+        match x {
+            Exp::SchemeString(x) => x,
+            _ => panic!("not a file name!"),
+        }
+    }
+
+    // closes the port when dropped, so it is closed on return and while
+    // unwinding from an error or exit as well
+    struct ClosePortGuard(Exp);
+
+    impl Drop for ClosePortGuard {
+        fn drop(&mut self) {
+            if let Exp::Port(x) = &self.0 {
+                if let Ok(mut x) = x.try_borrow_mut() {
+                    x.close();
                 }
-                t if t == "+".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        match lhs {
-                            Exp::Integer(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let r = x + y;
-                                    Some(Exp::Integer(r))
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let r = x as f32 + y;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                _ => panic!("wrong type for add!"),
-                            },
-                            Exp::FloatNumber(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let r = x + y as f32;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let r = x + y;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                _ => panic!("wrong type for add!"),
-                            },
-                            _ => panic!("wrong type for add!"),
-                        }
-                    } else {
-                        panic!("wrong number of args!");
-                    }
+            }
+        }
+    }
+
+    // (call-with-input-file name proc), (call-with-output-file name proc)
+    // and (with-output-to-file name thunk) close the port when f is done
+    fn call_with_file(port: Exp, f: Exp, as_current_port: bool) -> Exp {
+        let _close = ClosePortGuard(port.clone());
+        if as_current_port {
+            let mut guard = ParameterizeGuard::new();
+            guard.bind(current_output_port(), port);
+            call_procedure(&f, Exp::List(Pair::Nil))
+        } else {
+            call_procedure(&f, scheme_list!(port))
+        }
+    }
+
+    fn to_byte(x: Exp) -> u8 {
+        match x {
+            Exp::Integer(x) if (0..=255).contains(&x) => x as u8,
+            _ => panic!("not a byte!"),
+        }
+    }
+
+    fn to_bytevector(x: Exp) -> Rc<RefCell<Vec<u8>>> {
+        match x {
+            Exp::Bytevector(x) => x,
+            _ => panic!("not a bytevector!"),
+        }
+    }
+
+    // the optional start and end arguments of bytevector-copy, utf8->string
+    // and string->utf8, which default to the whole sequence
+    fn optional_range(rest: Exp, length: usize) -> (usize, usize) {
+        let index = |x: Exp| match x {
+            Exp::Integer(x) if x >= 0 && x as usize <= length => x as usize,
+            _ => panic!("index out of range!"),
+        };
+        let start = if rest.is_pair() {
+            index(car(rest.clone()).unwrap())
+        } else {
+            0
+        };
+        let end = if rest.is_pair() && cdr(rest.clone()).unwrap().is_pair() {
+            index(cadr(rest).unwrap())
+        } else {
+            length
+        };
+        if start > end {
+            panic!("index out of range!");
+        }
+        (start, end)
+    }
+
+    // (+ a b c) => (+ (+ (+ init a) b) c)
+    fn fold_numbers(args: Exp, init: Exp, operation: fn(Exp, Exp) -> Exp) -> Exp {
+        let mut result = init;
+        let mut rest = args;
+        while !no_operands(rest.clone()) {
+            result = operation(result, car(rest.clone()).unwrap());
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    // the only argument of a procedure that takes one
+    fn single_argument(args: Exp) -> Exp {
+        if list_length(args.clone()) != 1 {
+            panic!("wrong number of args!");
+        }
+        car(args).unwrap()
+    }
+
+    // the arguments of a procedure that takes two
+    fn two_arguments(args: Exp) -> (Exp, Exp) {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        (car(args.clone()).unwrap(), cadr(args).unwrap())
+    }
+
+    // (min x ...) and (max x ...), inexact if any argument is
+    fn extremum(args: Exp, keep: Ordering) -> Exp {
+        if no_operands(args.clone()) {
+            panic!("wrong number of args!");
+        }
+        let mut result = car(args.clone()).unwrap();
+        let mut inexact = !is_exact(&result);
+        let mut rest = cdr(args).unwrap();
+        while !no_operands(rest.clone()) {
+            let x = car(rest.clone()).unwrap();
+            inexact = inexact || !is_exact(&x);
+            if compare(&x, &result) == Some(keep) {
+                result = x;
+            }
+            rest = cdr(rest).unwrap();
+        }
+        if inexact {
+            exact_to_inexact(result)
+        } else {
+            result
+        }
+    }
+
+    // (< a b c) holds when every adjacent pair is in order, a NaN is
+    // never in order with anything
+    fn compare_numbers(args: Exp, in_order: fn(Ordering) -> bool) -> Exp {
+        if list_length(args.clone()) < 2 {
+            panic!("wrong number of args!");
+        }
+        let mut lhs = car(args.clone()).unwrap();
+        let mut rest = cdr(args).unwrap();
+        let mut result = true;
+        while !no_operands(rest.clone()) {
+            let rhs = car(rest.clone()).unwrap();
+            result = match compare(&lhs, &rhs) {
+                Some(x) => result && in_order(x),
+                None => false,
+            };
+            lhs = rhs;
+            rest = cdr(rest).unwrap();
+        }
+        Exp::Bool(result)
+    }
+
+    // (memq x lst) => the first sublist of lst whose car is x, or false
+    fn member_of(args: Exp, same: fn(&Exp, &Exp) -> bool) -> Exp {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        let x = car(args.clone()).unwrap();
+        let mut rest = cadr(args).unwrap();
+        while rest.is_pair() {
+            if same(&x, &car(rest.clone()).unwrap()) {
+                return rest;
+            }
+            rest = cdr(rest).unwrap();
+        }
+        Exp::Bool(false)
+    }
+
+    // (assq x alist) => the first pair in alist whose car is x, or false
+    fn association_of(args: Exp, same: fn(&Exp, &Exp) -> bool) -> Exp {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        let x = car(args.clone()).unwrap();
+        let mut rest = cadr(args).unwrap();
+        while rest.is_pair() {
+            let entry = car(rest.clone()).unwrap();
+            if !entry.is_pair() {
+                panic!("not an association list!");
+            }
+            if same(&x, &car(entry.clone()).unwrap()) {
+                return entry;
+            }
+            rest = cdr(rest).unwrap();
+        }
+        Exp::Bool(false)
+    }
+
+    // procedures passed to primitives, such as the predicate of filter,
+    // are called back through apply, a primitive has no way to return
+    // the error so it is raised as a panic
+    fn call_procedure(p: &Exp, args: Exp) -> Exp {
+        match apply(p.clone(), args) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    // (apply f 1 2 '(3 4)) => f and (1 2 3 4)
+    fn spread_arguments(args: Exp) -> (Exp, Exp) {
+        if list_length(args.clone()) < 2 {
+            panic!("wrong number of args!");
+        }
+        let f = car(args.clone()).unwrap();
+        let mut spread_args = reverse(cdr(args).unwrap());
+        let last = car(spread_args.clone()).unwrap();
+        if !last.is_list() {
+            panic!("the last argument of apply must be a list!");
+        }
+        spread_args = cdr(spread_args).unwrap();
+        (f, append(reverse(spread_args), last))
+    }
+
+    // (map f '(1 2) '(a b c)) => ((f 1 a) (f 2 b)), the shortest list
+    // decides the length of the result
+    fn map_lists(args: Exp, collect: bool) -> Exp {
+        if list_length(args.clone()) < 2 {
+            panic!("wrong number of args!");
+        }
+        let f = car(args.clone()).unwrap();
+        let mut lists = cdr(args).unwrap();
+        let mut result = Exp::List(Pair::Nil);
+        loop {
+            let mut heads = Exp::List(Pair::Nil);
+            let mut tails = Exp::List(Pair::Nil);
+            let mut rest = lists.clone();
+            while rest.is_pair() {
+                let x = car(rest.clone()).unwrap();
+                if !x.is_pair() {
+                    return reverse(result);
                 }
-                t if t == "-".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        match lhs {
-                            Exp::Integer(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let r = x - y;
-                                    Some(Exp::Integer(r))
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let r = x as f32 - y;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                _ => panic!("wrong type for substract!"),
-                            },
-                            Exp::FloatNumber(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let r = x - y as f32;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let r = x - y;
-                                    Some(Exp::FloatNumber(r))
-                                }
-                                _ => panic!("wrong type for substract!"),
-                            },
-                            _ => panic!("wrong type for substract!"),
-                        }
-                    } else {
-                        panic!("wrong number of args!");
-                    }
+                heads = scheme_cons(car(x.clone()).unwrap(), heads);
+                tails = scheme_cons(cdr(x).unwrap(), tails);
+                rest = cdr(rest).unwrap();
+            }
+            if collect {
+                result = scheme_cons(call_procedure(&f, reverse(heads)), result);
+            } else {
+                call_procedure(&f, reverse(heads));
+            }
+            lists = reverse(tails);
+        }
+    }
+
+    // stable merge sort, less? is called back for every comparison
+    fn merge_sort(x: Vec<Exp>, less: &Exp) -> Vec<Exp> {
+        if x.len() < 2 {
+            return x;
+        }
+        let mut left = x;
+        let right = merge_sort(left.split_off(left.len() / 2), less);
+        let left = merge_sort(left, less);
+        let mut result = Vec::with_capacity(left.len() + right.len());
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
+        while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+            // take from the right only when it is strictly less
+            if call_procedure(less, scheme_list!(y.clone(), x.clone())).is_true() {
+                result.push(right.next().unwrap());
+            } else {
+                result.push(left.next().unwrap());
+            }
+        }
+        result.extend(left);
+        result.extend(right);
+        result
+    }
+
+    // (append '(1) '(2) '(3 4)) => (1 2 3 4), the last list is shared
+    fn append_lists(args: Exp) -> Exp {
+        let mut rest = reverse(args);
+        if no_operands(rest.clone()) {
+            return Exp::List(Pair::Nil);
+        }
+        let mut result = car(rest.clone()).unwrap();
+        rest = cdr(rest).unwrap();
+        while rest.is_pair() {
+            result = append(car(rest.clone()).unwrap(), result);
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    // (filter even? '(1 2 3 4)) => (2 4), (remove even? '(1 2 3 4)) => (1 3)
+    fn filter_list(args: Exp, keep: bool) -> Exp {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        let predicate = car(args.clone()).unwrap();
+        let mut rest = cadr(args).unwrap();
+        let mut result = Exp::List(Pair::Nil);
+        while rest.is_pair() {
+            let x = car(rest.clone()).unwrap();
+            if call_procedure(&predicate, scheme_list!(x.clone())).is_true() == keep {
+                result = scheme_cons(x, result);
+            }
+            rest = cdr(rest).unwrap();
+        }
+        reverse(result)
+    }
+
+    // (fold-left f init '(a b)) => (f (f init a) b)
+    // (fold-right f init '(a b)) => (f a (f b init))
+    fn fold_list(args: Exp, from_left: bool) -> Exp {
+        if list_length(args.clone()) != 3 {
+            panic!("wrong number of args!");
+        }
+        let f = car(args.clone()).unwrap();
+        let mut result = cadr(args.clone()).unwrap();
+        let mut rest = car(cddr(args).unwrap()).unwrap();
+        if !from_left {
+            rest = reverse(rest);
+        }
+        while rest.is_pair() {
+            let x = car(rest.clone()).unwrap();
+            result = if from_left {
+                call_procedure(&f, scheme_list!(result, x))
+            } else {
+                call_procedure(&f, scheme_list!(x, result))
+            };
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    // (reduce + 0 '(1 2 3)) => (+ 3 (+ 2 1))
+    fn reduce_list(args: Exp) -> Exp {
+        if list_length(args.clone()) != 3 {
+            panic!("wrong number of args!");
+        }
+        let f = car(args.clone()).unwrap();
+        let mut rest = car(cddr(args.clone()).unwrap()).unwrap();
+        if !rest.is_pair() {
+            return cadr(args).unwrap();
+        }
+        let mut result = car(rest.clone()).unwrap();
+        rest = cdr(rest).unwrap();
+        while rest.is_pair() {
+            result = call_procedure(&f, scheme_list!(car(rest.clone()).unwrap(), result));
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    // (any f lst) => the first true value of f, or false
+    // (every f lst) => the last value of f if none of them is false
+    fn any_or_every(args: Exp, any: bool) -> Exp {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        let predicate = car(args.clone()).unwrap();
+        let mut rest = cadr(args).unwrap();
+        let mut result = Exp::Bool(!any);
+        while rest.is_pair() {
+            result = call_procedure(&predicate, scheme_list!(car(rest.clone()).unwrap()));
+            if result.is_true() == any {
+                return result;
+            }
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    // (iota 3) => (0 1 2), (iota 3 1 2) => (1 3 5)
+    fn iota(args: Exp) -> Exp {
+        let length = list_length(args.clone());
+        if !(1..=3).contains(&length) {
+            panic!("wrong number of args!");
+        }
+        let count = car(args.clone()).unwrap();
+        if !count.is_integer() || count.to_i64() < 0 {
+            panic!("wrong type for iota!");
+        }
+        let start = if length > 1 {
+            cadr(args.clone()).unwrap()
+        } else {
+            Exp::Integer(0)
+        };
+        let step = if length > 2 {
+            car(cddr(args).unwrap()).unwrap()
+        } else {
+            Exp::Integer(1)
+        };
+        let mut result = Exp::List(Pair::Nil);
+        for i in (0..count.to_i64()).rev() {
+            let x = add(start.clone(), multiply(Exp::Integer(i), step.clone()));
+            result = scheme_cons(x, result);
+        }
+        result
+    }
+
+    // the list argument of list-ref and list-tail
+    fn list_index(args: Exp) -> (Exp, i64) {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        let k = cadr(args.clone()).unwrap();
+        if !k.is_integer() || k.to_i64() < 0 {
+            panic!("wrong type for list index!");
+        }
+        (car(args).unwrap(), k.to_i64())
+    }
+
+    // display or write, to the current output port by default
+    fn write_object(args: Exp, write: bool) -> Exp {
+        let obj = car(args.clone()).unwrap();
+        let port = output_port(optional_argument(args, 1));
+        port.borrow_mut().write_str(&exp_to_string(&obj, write));
+        Exp::Unspecified
+    }
+
+    fn read_character(args: Exp, peek: bool) -> Exp {
+        let port = input_port(optional_argument(args, 0));
+        let c = if peek {
+            port.borrow_mut().peek_char()
+        } else {
+            port.borrow_mut().read_char()
+        };
+        match c {
+            Some(c) => Exp::Character(c),
+            None => Exp::Eof,
+        }
+    }
+
+    // call-with-output-file, or with-output-to-file when redirect is set
+    fn call_with_output_file(args: Exp, redirect: bool) -> Exp {
+        let (name, f) = two_arguments(args);
+        call_with_file(open_output_file(&file_name(name)), f, redirect)
+    }
+
+    fn close_port(args: Exp) -> Exp {
+        match car(args).unwrap() {
+            Exp::Port(x) => x.borrow_mut().close(),
+            _ => panic!("not a port!"),
+        }
+        Exp::Unspecified
+    }
+
+    fn divide_integers(args: Exp, operator: &str) -> Exp {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        integer_division(car(args.clone()).unwrap(), cadr(args).unwrap(), operator)
+    }
+
+    // there are no multiple values, the quotient and the remainder come
+    // back as a list
+    fn quotient_and_remainder(args: Exp, quotient: &str, remainder: &str) -> Exp {
+        if list_length(args.clone()) != 2 {
+            panic!("wrong number of args!");
+        }
+        let lhs = car(args.clone()).unwrap();
+        let rhs = cadr(args).unwrap();
+        scheme_list!(
+            integer_division(lhs.clone(), rhs.clone(), quotient),
+            integer_division(lhs, rhs, remainder)
+        )
+    }
+
+    fn is_exact_number(x: Exp) -> bool {
+        match x {
+            Exp::Complex(real, imag) => is_exact(&real) && is_exact(&imag),
+            x if x.is_number() => is_exact(&x),
+            _ => panic!("not a number!"),
+        }
+    }
+
+    // primitive procedures bound in the global environment
+    const PRIMITIVE_PROCEDURES: [(&str, PrimitiveProcedure); 165] = [
+        ("*", |args| fold_numbers(args, Exp::Integer(1), multiply)),
+        ("/", |args| match list_length(args.clone()) {
+            0 => panic!("wrong number of args!"),
+            1 => divide(Exp::Integer(1), car(args).unwrap()),
+            _ => fold_numbers(cdr(args.clone()).unwrap(), car(args).unwrap(), divide),
+        }),
+        ("+", |args| fold_numbers(args, Exp::Integer(0), add)),
+        ("-", |args| match list_length(args.clone()) {
+            0 => panic!("wrong number of args!"),
+            1 => negate(car(args).unwrap()),
+            _ => fold_numbers(cdr(args.clone()).unwrap(), car(args).unwrap(), substract),
+        }),
+        ("car", |args| {
+            if args.is_pair() {
+                caar(args).unwrap()
+            } else {
+                panic!("not a proper schemem list: car");
+            }
+        }),
+        ("cdr", |args| {
+            if args.is_pair() {
+                cdar(args).unwrap()
+            } else {
+                panic!("not a proper schemem list: cdr");
+            }
+        }),
+        ("cons", |args| {
+            if list_length(args.clone()) == 2 {
+                let lhs = car(args.clone()).unwrap();
+                let rhs = cadr(args.clone()).unwrap();
+                scheme_cons(lhs, rhs)
+            } else if list_length(args.clone()) == 1 {
+                let lhs = car(args.clone());
+                match lhs {
+                    Ok(x) => scheme_cons(x, Exp::List(Pair::Nil)),
+                    Err(e) => panic!("objects of cons must contain at least one element!"),
                 }
-                t if t == "car".to_string() => {
-                    if args.is_pair() {
-                        Some(caar(args).unwrap())
-                    } else {
-                        panic!("not a proper schemem list: car");
-                    }
+            } else {
+                panic!("not a proper schemem list: cons");
+            }
+        }),
+        ("null?", |args| {
+            if list_length(args.clone()) == 1 {
+                if car(args).unwrap() == Exp::List(Pair::Nil) {
+                    Exp::Bool(true)
+                } else {
+                    Exp::Bool(false)
                 }
-                t if t == "cdr".to_string() => {
-                    if args.is_pair() {
-                        Some(cdar(args).unwrap())
-                    } else {
-                        panic!("not a proper schemem list: cdr");
-                    }
+            } else {
+                panic!("not a proper schemem list: cons");
+            }
+        }),
+        ("=", |args| {
+            if list_length(args.clone()) >= 2 {
+                let mut lhs = car(args.clone()).unwrap();
+                let mut rest = cdr(args).unwrap();
+                let mut result = true;
+                while !no_operands(rest.clone()) {
+                    let rhs = car(rest.clone()).unwrap();
+                    result = is_numerically_equal(&lhs, &rhs) && result;
+                    lhs = rhs;
+                    rest = cdr(rest).unwrap();
                 }
-                t if t == "cons".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        Some(scheme_cons(lhs, rhs))
-                    } else if list_length(args.clone()) == 1 {
-                        let lhs = car(args.clone());
-                        match lhs {
-                            Ok(x) => Some(scheme_cons(x, Exp::List(Pair::Nil))),
-                            Err(e) => panic!("objects of cons must contain at least one element!"),
-                        }
-                    } else {
-                        panic!("not a proper schemem list: cons");
-                    }
+                Exp::Bool(result)
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        (">", |args| {
+            compare_numbers(args, |x| x == Ordering::Greater)
+        }),
+        ("<", |args| compare_numbers(args, |x| x == Ordering::Less)),
+        (">=", |args| compare_numbers(args, |x| x != Ordering::Less)),
+        ("<=", |args| {
+            compare_numbers(args, |x| x != Ordering::Greater)
+        }),
+        ("display", |args| write_object(args, false)),
+        ("write", |args| write_object(args, true)),
+        ("newline", |args| {
+            output_port(optional_argument(args, 0))
+                .borrow_mut()
+                .write_str("\n");
+            Exp::Unspecified
+        }),
+        ("write-char", |args| match car(args.clone()).unwrap() {
+            Exp::Character(x) => {
+                let port = output_port(optional_argument(args, 1));
+                port.borrow_mut().write_str(&x.to_string());
+                Exp::Unspecified
+            }
+            _ => panic!("wrong type for write-char!"),
+        }),
+        ("write-string", |args| match car(args.clone()).unwrap() {
+            Exp::SchemeString(x) => {
+                let port = output_port(optional_argument(args.clone(), 1));
+                let chars: Vec<char> = x.chars().collect();
+                let range = if list_length(args.clone()) > 2 {
+                    cddr(args).unwrap()
+                } else {
+                    Exp::List(Pair::Nil)
+                };
+                let (start, end) = optional_range(range, chars.len());
+                let s: String = chars[start..end].iter().collect();
+                port.borrow_mut().write_str(&s);
+                Exp::Unspecified
+            }
+            _ => panic!("wrong type for write-string!"),
+        }),
+        ("flush-output-port", |args| {
+            output_port(optional_argument(args, 0)).borrow_mut().flush();
+            Exp::Unspecified
+        }),
+        ("open-input-string", |args| match car(args).unwrap() {
+            Exp::SchemeString(x) => make_port(StringInputPort::new(&x)),
+            _ => panic!("wrong type for open-input-string!"),
+        }),
+        ("open-output-string", |_| {
+            make_port(StringOutputPort::default())
+        }),
+        ("get-output-string", |args| match car(args).unwrap() {
+            Exp::Port(x) => match x.borrow().output_string() {
+                Some(s) => Exp::SchemeString(s),
+                None => panic!("not a string output port!"),
+            },
+            _ => panic!("wrong type for get-output-string!"),
+        }),
+        ("with-output-to-string", |args| {
+            output_to_string(single_argument(args), true)
+        }),
+        ("call-with-output-string", |args| {
+            output_to_string(single_argument(args), false)
+        }),
+        ("read-char", |args| read_character(args, false)),
+        ("peek-char", |args| read_character(args, true)),
+        ("read-line", |args| {
+            let port = input_port(optional_argument(args, 0));
+            let mut port = port.borrow_mut();
+            let mut line = String::new();
+            loop {
+                match port.read_char() {
+                    Some('\n') => break,
+                    Some(c) => line.push(c),
+                    None if line.is_empty() => return Exp::Eof,
+                    None => break,
                 }
-                t if t == "null?".to_string() => {
-                    if list_length(args.clone()) == 1 {
-                        if car(args).unwrap() == Exp::List(Pair::Nil) {
-                            Some(Exp::Bool(true))
-                        } else {
-                            Some(Exp::Bool(false))
-                        }
-                    } else {
-                        panic!("not a proper schemem list: cons");
-                    }
+            }
+            Exp::SchemeString(line)
+        }),
+        ("read-string", |args| {
+            let k = match car(args.clone()).unwrap() {
+                Exp::Integer(k) if k >= 0 => k,
+                _ => panic!("wrong type for read-string!"),
+            };
+            let port = input_port(optional_argument(args, 1));
+            let mut port = port.borrow_mut();
+            let mut s = String::new();
+            for _ in 0..k {
+                match port.read_char() {
+                    Some(c) => s.push(c),
+                    None => break,
                 }
-                t if t == "=".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        if lhs == rhs {
-                            Some(Exp::Bool(true))
-                        } else {
-                            Some(Exp::Bool(false))
-                        }
-                    } else {
-                        panic!("wrong number of args!");
-                    }
+            }
+            if s.is_empty() && k > 0 {
+                Exp::Eof
+            } else {
+                Exp::SchemeString(s)
+            }
+        }),
+        ("open-input-file", |args| {
+            make_port(FileInputPort::open(&file_name(car(args).unwrap())))
+        }),
+        ("open-output-file", |args| {
+            open_output_file(&file_name(car(args).unwrap()))
+        }),
+        ("call-with-input-file", |args| {
+            let (name, f) = two_arguments(args);
+            call_with_file(make_port(FileInputPort::open(&file_name(name))), f, false)
+        }),
+        ("call-with-output-file", |args| {
+            call_with_output_file(args, false)
+        }),
+        ("with-output-to-file", |args| {
+            call_with_output_file(args, true)
+        }),
+        ("char-ready?", |args| {
+            Exp::Bool(
+                input_port(optional_argument(args, 0))
+                    .borrow_mut()
+                    .char_ready(),
+            )
+        }),
+        ("close-port", |args| close_port(args)),
+        ("close-input-port", |args| close_port(args)),
+        ("close-output-port", |args| close_port(args)),
+        ("read", |args| {
+            read_from_port(&mut *input_port(optional_argument(args, 0)).borrow_mut())
+        }),
+        ("load", |args| {
+            load(&file_name(car(args).unwrap()));
+            Exp::Unspecified
+        }),
+        ("eval", |args| {
+            let exp = car(args.clone()).unwrap();
+            match optional_argument(args, 1) {
+                Some(Exp::Environment(Environment::Captured(env))) => {
+                    let _guard = LibraryGuard::captured(env.clone());
+                    let env = Env::clone(&env.borrow());
+                    eval(exp, env).unwrap()
                 }
-                t if t == ">".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        match lhs {
-                            Exp::Integer(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    if x > y {
-                                        Some(Exp::Bool(true))
-                                    } else {
-                                        Some(Exp::Bool(false))
-                                    }
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let t = x as f32;
-                                    if t > y {
-                                        Some(Exp::Bool(true))
-                                    } else {
-                                        Some(Exp::Bool(false))
-                                    }
-                                }
-                                _ => panic!("type mismatch for comparision!"),
-                            },
-                            Exp::FloatNumber(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let t = y as f32;
-                                    if x > t {
-                                        Some(Exp::Bool(true))
-                                    } else {
-                                        Some(Exp::Bool(false))
-                                    }
-                                }
-                                Exp::FloatNumber(y) => {
-                                    if x > y {
-                                        Some(Exp::Bool(true))
-                                    } else {
-                                        Some(Exp::Bool(false))
-                                    }
-                                }
-                                _ => panic!("type mismatch for comparision!"),
-                            },
-                            _ => panic!("type mismatch for comparision"),
-                        }
-                    } else {
-                        panic!("wrong number of ars!");
-                    }
+                None | Some(Exp::Environment(Environment::Global)) => {
+                    let _guard = LibraryGuard::new(None);
+                    eval(exp, Environment::Global.env()).unwrap()
                 }
-                t if t == "<".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        match lhs {
-                            Exp::Integer(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    if x > y {
-                                        Some(Exp::Bool(false))
-                                    } else {
-                                        Some(Exp::Bool(true))
-                                    }
-                                }
-                                Exp::FloatNumber(y) => {
-                                    let t = x as f32;
-                                    if t > y {
-                                        Some(Exp::Bool(false))
-                                    } else {
-                                        Some(Exp::Bool(true))
-                                    }
-                                }
-                                _ => panic!("type mismatch for comparision!"),
-                            },
-                            Exp::FloatNumber(x) => match rhs {
-                                Exp::Integer(y) => {
-                                    let t = y as f32;
-                                    if x > t {
-                                        Some(Exp::Bool(false))
-                                    } else {
-                                        Some(Exp::Bool(true))
-                                    }
-                                }
-                                Exp::FloatNumber(y) => {
-                                    if x > y {
-                                        Some(Exp::Bool(false))
-                                    } else {
-                                        Some(Exp::Bool(true))
-                                    }
-                                }
-                                _ => panic!("type mismatch for comparision!"),
-                            },
-                            _ => panic!("type mismatch for comparision"),
-                        }
-                    } else {
-                        panic!("wrong number of ars!");
-                    }
+                _ => panic!("not an environment!"),
+            }
+        }),
+        ("interaction-environment", |_| {
+            Exp::Environment(Environment::Global)
+        }),
+        ("environment?", |args| {
+            Exp::Bool(matches!(car(args).unwrap(), Exp::Environment(_)))
+        }),
+        ("eof-object", |_| Exp::Eof),
+        ("eof-object?", |args| {
+            Exp::Bool(car(args).unwrap() == Exp::Eof)
+        }),
+        ("port?", |args| Exp::Bool(car(args).unwrap().is_port())),
+        ("input-port?", |args| match car(args).unwrap() {
+            Exp::Port(x) => Exp::Bool(x.borrow().is_input()),
+            _ => Exp::Bool(false),
+        }),
+        ("output-port?", |args| match car(args).unwrap() {
+            Exp::Port(x) => Exp::Bool(x.borrow().is_output()),
+            _ => Exp::Bool(false),
+        }),
+        ("eq?", |args| {
+            if list_length(args.clone()) == 2 {
+                let lhs = car(args.clone()).unwrap();
+                let rhs = cadr(args.clone()).unwrap();
+                Exp::Bool(lhs.is_eq(&rhs))
+            } else {
+                panic!("eq? operation must have only two objects!");
+            }
+        }),
+        ("eqv?", |args| {
+            if list_length(args.clone()) == 2 {
+                let lhs = car(args.clone()).unwrap();
+                let rhs = cadr(args.clone()).unwrap();
+                Exp::Bool(lhs.is_eqv(&rhs))
+            } else {
+                panic!("eqv? operation must have only two objects!");
+            }
+        }),
+        ("equal?", |args| {
+            if list_length(args.clone()) == 2 {
+                let lhs = car(args.clone()).unwrap();
+                let rhs = cadr(args.clone()).unwrap();
+                Exp::Bool(lhs.is_equal(&rhs))
+            } else {
+                panic!("equal? operation must have only two objects!");
+            }
+        }),
+        ("memq", |args| member_of(args, Exp::is_eq)),
+        ("memv", |args| member_of(args, Exp::is_eqv)),
+        ("member", |args| member_of(args, Exp::is_equal)),
+        ("assq", |args| association_of(args, Exp::is_eq)),
+        ("assv", |args| association_of(args, Exp::is_eqv)),
+        ("assoc", |args| association_of(args, Exp::is_equal)),
+        ("symbol?", |args| {
+            if list_length(args.clone()) == 1 {
+                Exp::Bool(car(args).unwrap().is_symbol())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("list", |args| args),
+        ("length", |args| {
+            let x = car(args).unwrap();
+            if x.is_list() {
+                Exp::Integer(list_length(x) as i64)
+            } else {
+                panic!("not a proper schemem list: length");
+            }
+        }),
+        ("append", |args| append_lists(args)),
+        ("reverse", |args| reverse(car(args).unwrap())),
+        ("list-tail", |args| {
+            let (x, k) = list_index(args);
+            list_tail(x, k)
+        }),
+        ("list-ref", |args| {
+            let (x, k) = list_index(args);
+            let tail = list_tail(x, k);
+            if tail.is_pair() {
+                car(tail).unwrap()
+            } else {
+                panic!("index out of range!");
+            }
+        }),
+        ("last-pair", |args| last_pair(car(args).unwrap())),
+        ("list-copy", |args| {
+            append(car(args).unwrap(), Exp::List(Pair::Nil))
+        }),
+        ("pair?", |args| Exp::Bool(car(args).unwrap().is_pair())),
+        ("list?", |args| Exp::Bool(car(args).unwrap().is_list())),
+        ("apply", |args| {
+            let (f, spread_args) = spread_arguments(args);
+            call_procedure(&f, spread_args)
+        }),
+        ("map", |args| map_lists(args, true)),
+        ("for-each", |args| {
+            map_lists(args, false);
+            Exp::Unspecified
+        }),
+        ("bytevector", |args| {
+            let bytes = list_to_vec(args).into_iter().map(to_byte).collect();
+            Exp::Bytevector(Rc::new(RefCell::new(bytes)))
+        }),
+        ("bytevector?", |args| {
+            Exp::Bool(car(args).unwrap().is_bytevector())
+        }),
+        ("make-bytevector", |args| {
+            let length = match car(args.clone()).unwrap() {
+                Exp::Integer(x) if x >= 0 => x as usize,
+                _ => panic!("wrong type for make-bytevector!"),
+            };
+            let fill = if list_length(args.clone()) > 1 {
+                to_byte(cadr(args).unwrap())
+            } else {
+                0
+            };
+            Exp::Bytevector(Rc::new(RefCell::new(vec![fill; length])))
+        }),
+        ("bytevector-length", |args| {
+            let x = to_bytevector(car(args).unwrap());
+            let length = x.borrow().len();
+            Exp::Integer(length as i64)
+        }),
+        ("bytevector-u8-ref", |args| {
+            let x = to_bytevector(car(args.clone()).unwrap());
+            let byte = match cadr(args).unwrap() {
+                Exp::Integer(k) if k >= 0 => x.borrow().get(k as usize).copied(),
+                _ => None,
+            };
+            match byte {
+                Some(byte) => Exp::Integer(byte as i64),
+                None => panic!("index out of range!"),
+            }
+        }),
+        ("bytevector-u8-set!", |args| {
+            if list_length(args.clone()) != 3 {
+                panic!("wrong number of args!");
+            }
+            let x = to_bytevector(car(args.clone()).unwrap());
+            let byte = to_byte(car(cddr(args.clone()).unwrap()).unwrap());
+            let mut x = x.borrow_mut();
+            match cadr(args).unwrap() {
+                Exp::Integer(k) if k >= 0 && (k as usize) < x.len() => {
+                    x[k as usize] = byte;
                 }
-                t if t == "display".to_string() => {
-                    let x = car(args).unwrap();
-                    pretty_print(x);
-                    None
+                _ => panic!("index out of range!"),
+            }
+            Exp::Unspecified
+        }),
+        ("bytevector-copy", |args| {
+            let x = to_bytevector(car(args.clone()).unwrap());
+            let x = x.borrow();
+            let (start, end) = optional_range(cdr(args).unwrap(), x.len());
+            Exp::Bytevector(Rc::new(RefCell::new(x[start..end].to_vec())))
+        }),
+        ("bytevector-append", |args| {
+            let mut bytes = vec![];
+            for x in list_to_vec(args) {
+                bytes.extend_from_slice(&to_bytevector(x).borrow());
+            }
+            Exp::Bytevector(Rc::new(RefCell::new(bytes)))
+        }),
+        ("utf8->string", |args| {
+            let x = to_bytevector(car(args.clone()).unwrap());
+            let x = x.borrow();
+            let (start, end) = optional_range(cdr(args).unwrap(), x.len());
+            match String::from_utf8(x[start..end].to_vec()) {
+                Ok(s) => Exp::SchemeString(s),
+                Err(_) => panic!("invalid utf-8 sequence!"),
+            }
+        }),
+        ("string->utf8", |args| match car(args.clone()).unwrap() {
+            Exp::SchemeString(x) => {
+                let chars: Vec<char> = x.chars().collect();
+                let (start, end) = optional_range(cdr(args).unwrap(), chars.len());
+                let s: String = chars[start..end].iter().collect();
+                Exp::Bytevector(Rc::new(RefCell::new(s.into_bytes())))
+            }
+            _ => panic!("wrong type for string->utf8!"),
+        }),
+        ("make-parameter", |args| {
+            let length = list_length(args.clone());
+            if length != 1 && length != 2 {
+                panic!("wrong number of args!");
+            }
+            let converter = if length == 2 {
+                Some(cadr(args.clone()).unwrap())
+            } else {
+                None
+            };
+            let mut p = Parameter {
+                value: RefCell::new(Exp::Bool(false)),
+                converter,
+            };
+            p.value = RefCell::new(convert_parameter_value(&p, car(args).unwrap()));
+            Exp::Parameter(Rc::new(p))
+        }),
+        ("vector", |args| Exp::Vector(Rc::new(list_to_vec(args)))),
+        ("vector?", |args| Exp::Bool(car(args).unwrap().is_vector())),
+        ("vector-length", |args| match car(args).unwrap() {
+            Exp::Vector(x) => Exp::Integer(x.len() as i64),
+            _ => panic!("wrong type for vector-length!"),
+        }),
+        ("vector-ref", |args| {
+            let (x, k) = (car(args.clone()).unwrap(), cadr(args).unwrap());
+            match (x, k) {
+                (Exp::Vector(x), Exp::Integer(k)) => match x.get(k as usize) {
+                    Some(item) if k >= 0 => item.clone(),
+                    _ => panic!("index out of range!"),
+                },
+                _ => panic!("wrong type for vector-ref!"),
+            }
+        }),
+        ("vector->list", |args| match car(args).unwrap() {
+            Exp::Vector(x) => vec_to_list(&x),
+            _ => panic!("wrong type for vector->list!"),
+        }),
+        ("list->vector", |args| {
+            Exp::Vector(Rc::new(list_to_vec(car(args).unwrap())))
+        }),
+        ("vector-map", |args| {
+            if list_length(args.clone()) < 2 {
+                panic!("wrong number of args!");
+            }
+            let f = car(args.clone()).unwrap();
+            let mut lists = Exp::List(Pair::Nil);
+            let mut rest = reverse(cdr(args).unwrap());
+            while rest.is_pair() {
+                match car(rest.clone()).unwrap() {
+                    Exp::Vector(x) => lists = scheme_cons(vec_to_list(&x), lists),
+                    _ => panic!("wrong type for vector-map!"),
                 }
-                t if t == "eq?".to_string() => {
-                    if list_length(args.clone()) == 2 {
-                        let lhs = car(args.clone()).unwrap();
-                        let rhs = cadr(args.clone()).unwrap();
-                        if lhs == rhs {
-                            Some(Exp::Bool(true))
-                        } else {
-                            Some(Exp::Bool(false))
-                        }
-                    } else {
-                        panic!("eq? operation must have only two objects!");
-                    }
+                rest = cdr(rest).unwrap();
+            }
+            let result = map_lists(scheme_cons(f, lists), true);
+            Exp::Vector(Rc::new(list_to_vec(result)))
+        }),
+        ("sort", |args| {
+            if list_length(args.clone()) != 2 {
+                panic!("wrong number of args!");
+            }
+            let less = cadr(args.clone()).unwrap();
+            match car(args).unwrap() {
+                Exp::Vector(x) => Exp::Vector(Rc::new(merge_sort(x.to_vec(), &less))),
+                x if x.is_list() => vec_to_list(&merge_sort(list_to_vec(x), &less)),
+                _ => panic!("wrong type for sort!"),
+            }
+        }),
+        ("filter", |args| filter_list(args, true)),
+        ("remove", |args| filter_list(args, false)),
+        ("reduce", |args| reduce_list(args)),
+        ("fold-left", |args| fold_list(args, true)),
+        ("fold-right", |args| fold_list(args, false)),
+        ("delete", |args| {
+            if list_length(args.clone()) != 2 {
+                panic!("wrong number of args!");
+            }
+            let x = car(args.clone()).unwrap();
+            let mut rest = cadr(args).unwrap();
+            let mut result = Exp::List(Pair::Nil);
+            while rest.is_pair() {
+                let y = car(rest.clone()).unwrap();
+                if !x.is_equal(&y) {
+                    result = scheme_cons(y, result);
                 }
+                rest = cdr(rest).unwrap();
+            }
+            reverse(result)
+        }),
+        ("iota", |args| iota(args)),
+        ("any", |args| any_or_every(args, true)),
+        ("every", |args| any_or_every(args, false)),
+        ("symbol->string", |args| match car(args).unwrap() {
+            Exp::Symbol(x) => Exp::SchemeString(x.as_str().to_string()),
+            _ => panic!("wrong type for symbol->string!"),
+        }),
+        ("string->symbol", |args| match car(args).unwrap() {
+            Exp::SchemeString(x) => Exp::Symbol(Symbol::new(&x)),
+            _ => panic!("wrong type for string->symbol!"),
+        }),
+        ("numerator", |args| {
+            if list_length(args.clone()) == 1 {
+                numerator(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("denominator", |args| {
+            if list_length(args.clone()) == 1 {
+                denominator(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("exact->inexact", |args| {
+            if list_length(args.clone()) == 1 {
+                exact_to_inexact(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("inexact->exact", |args| {
+            if list_length(args.clone()) == 1 {
+                inexact_to_exact(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("rationalize", |args| {
+            if list_length(args.clone()) == 2 {
+                let x = car(args.clone()).unwrap();
+                let y = cadr(args.clone()).unwrap();
+                rationalize(x, y)
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("make-rectangular", |args| {
+            if list_length(args.clone()) == 2 {
+                let x = car(args.clone()).unwrap();
+                let y = cadr(args.clone()).unwrap();
+                make_rectangular(x, y)
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("make-polar", |args| {
+            if list_length(args.clone()) == 2 {
+                let x = car(args.clone()).unwrap();
+                let y = cadr(args.clone()).unwrap();
+                make_polar(x, y)
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("real-part", |args| {
+            if list_length(args.clone()) == 1 {
+                real_part(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("imag-part", |args| {
+            if list_length(args.clone()) == 1 {
+                imag_part(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("magnitude", |args| {
+            if list_length(args.clone()) == 1 {
+                magnitude(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("angle", |args| {
+            if list_length(args.clone()) == 1 {
+                angle(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("sqrt", |args| {
+            if list_length(args.clone()) == 1 {
+                sqrt(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("exp", |args| {
+            if list_length(args.clone()) == 1 {
+                exp(car(args).unwrap())
+            } else {
+                panic!("wrong number of args!");
+            }
+        }),
+        ("log", |args| match list_length(args.clone()) {
+            1 => log(car(args).unwrap()),
+            // (log z b) is the logarithm of z in base b
+            2 => divide(log(car(args.clone()).unwrap()), log(cadr(args).unwrap())),
+            _ => panic!("wrong number of args!"),
+        }),
+        ("abs", |args| abs(single_argument(args))),
+        ("square", |args| {
+            let x = single_argument(args);
+            multiply(x.clone(), x)
+        }),
+        ("exact-integer-sqrt", |args| {
+            let (s, r) = exact_integer_sqrt(single_argument(args));
+            scheme_list!(s, r)
+        }),
+        ("expt", |args| {
+            if list_length(args.clone()) != 2 {
+                panic!("wrong number of args!");
+            }
+            expt(car(args.clone()).unwrap(), cadr(args).unwrap())
+        }),
+        ("sin", |args| trigonometric(single_argument(args), "sin")),
+        ("cos", |args| trigonometric(single_argument(args), "cos")),
+        ("tan", |args| trigonometric(single_argument(args), "tan")),
+        ("asin", |args| trigonometric(single_argument(args), "asin")),
+        ("acos", |args| trigonometric(single_argument(args), "acos")),
+        ("atan", |args| match list_length(args.clone()) {
+            1 => trigonometric(car(args).unwrap(), "atan"),
+            2 => atan2(car(args.clone()).unwrap(), cadr(args).unwrap()),
+            _ => panic!("wrong number of args!"),
+        }),
+        ("floor", |args| floor(single_argument(args))),
+        ("ceiling", |args| ceiling(single_argument(args))),
+        ("round", |args| round(single_argument(args))),
+        ("truncate", |args| truncate(single_argument(args))),
+        ("quotient", |args| divide_integers(args, "quotient")),
+        ("remainder", |args| divide_integers(args, "remainder")),
+        ("modulo", |args| divide_integers(args, "modulo")),
+        ("floor/", |args| {
+            quotient_and_remainder(args, "floor-quotient", "floor-remainder")
+        }),
+        ("truncate/", |args| {
+            quotient_and_remainder(args, "truncate-quotient", "truncate-remainder")
+        }),
+        ("gcd", |args| {
+            fold_numbers(args, Exp::Integer(0), integer_gcd)
+        }),
+        ("lcm", |args| {
+            fold_numbers(args, Exp::Integer(1), integer_lcm)
+        }),
+        ("min", |args| extremum(args, Ordering::Less)),
+        ("max", |args| extremum(args, Ordering::Greater)),
+        ("zero?", |args| {
+            Exp::Bool(is_numerically_equal(
+                &single_argument(args),
+                &Exp::Integer(0),
+            ))
+        }),
+        ("positive?", |args| {
+            Exp::Bool(compare(&single_argument(args), &Exp::Integer(0)) == Some(Ordering::Greater))
+        }),
+        ("negative?", |args| {
+            Exp::Bool(compare(&single_argument(args), &Exp::Integer(0)) == Some(Ordering::Less))
+        }),
+        ("odd?", |args| Exp::Bool(is_odd(&single_argument(args)))),
+        ("even?", |args| Exp::Bool(!is_odd(&single_argument(args)))),
+        ("exact?", |args| {
+            Exp::Bool(is_exact_number(single_argument(args)))
+        }),
+        ("inexact?", |args| {
+            Exp::Bool(!is_exact_number(single_argument(args)))
+        }),
+        ("bitwise-and", |args| {
+            fold_numbers(args, Exp::Integer(-1), bitwise_and)
+        }),
+        ("bitwise-or", |args| {
+            fold_numbers(args, Exp::Integer(0), bitwise_or)
+        }),
+        ("bitwise-xor", |args| {
+            fold_numbers(args, Exp::Integer(0), bitwise_xor)
+        }),
+        ("bitwise-not", |args| bitwise_not(single_argument(args))),
+        ("arithmetic-shift", |args| {
+            if list_length(args.clone()) != 2 {
+                panic!("wrong number of args!");
+            }
+            arithmetic_shift(car(args.clone()).unwrap(), cadr(args).unwrap())
+        }),
+        ("bit-count", |args| bit_count(single_argument(args))),
+        ("integer-length", |args| {
+            integer_length(single_argument(args))
+        }),
+        ("bit-set?", |args| {
+            if list_length(args.clone()) != 2 {
+                panic!("wrong number of args!");
+            }
+            Exp::Bool(is_bit_set(car(args.clone()).unwrap(), cadr(args).unwrap()))
+        }),
+        ("random", |args| random(single_argument(args))),
+        ("random-seed!", |args| {
+            random_seed(single_argument(args));
+            Exp::Unspecified
+        }),
+        ("runtime", |_| Exp::FloatNumber(cpu_time())),
+        ("real-time", |_| Exp::FloatNumber(current_second() * 1000.0)),
+        ("current-time", |_| Exp::Integer(current_second() as i64)),
+        ("current-second", |_| Exp::FloatNumber(current_second())),
+        ("current-jiffy", |_| Exp::Integer(current_jiffy())),
+        ("jiffies-per-second", |_| Exp::Integer(JIFFIES_PER_SECOND)),
+        ("command-line", |_| command_line()),
+        ("exit", |args| exit(optional_argument(args, 0))),
+        ("emergency-exit", |args| {
+            emergency_exit(optional_argument(args, 0))
+        }),
+        ("get-environment-variable", |args| {
+            get_environment_variable(single_argument(args))
+        }),
+        ("get-environment-variables", |_| get_environment_variables()),
+    ];
+
+    // the primitive procedures indexed by the id of their names, so
+    // applying one is an array lookup
+    static PRIMITIVE_TABLE: LazyLock<Vec<Option<PrimitiveProcedure>>> = LazyLock::new(|| {
+        let mut table = vec![];
+        for (name, procedure) in PRIMITIVE_PROCEDURES.iter() {
+            let id = Symbol::new(name).id();
+            if table.len() <= id {
+                table.resize(id + 1, None);
+            }
+            table[id] = Some(*procedure);
+        }
+        table
+    });
+
+    fn apply_primitive_procedure(p: Exp, args: Exp) -> Exp {
+        if let Exp::Primitive(x) = p {
+            match PRIMITIVE_TABLE.get(x.id()) {
+                Some(Some(procedure)) => procedure(args),
                 _ => panic!("attemp to run a primitive procedure that is not implemented yet!"),
             }
         } else {
@@ -549,43 +1916,103 @@ pub mod core_of_interpreter {
 }
 
 #[cfg(test)]
-mod test {
-    use crate::core_of_interpreter::core_of_interpreter::{eval, Exp, Pair, ENV};
+#[allow(static_mut_refs)]
+pub(crate) mod test {
+    use crate::core_of_interpreter::core_of_interpreter::{
+        eval, setup_environment, Exp, Pair, ENV,
+    };
+    use crate::display::display::exp_to_string;
+    use crate::environment::env::define_variable;
+    use crate::library::library::library_search_path;
+    use crate::parser::parser::{build_syntax_tree, tokenize};
+    use crate::port::port::{
+        current_output_port, flush_output_ports, set_current_output_port, Port,
+    };
+    use crate::process::process::run_until_exit;
     use crate::scheme_list;
+    use crate::symbol::symbol::{Symbol, QUOTE};
     use crate::tool::tools::{append, scheme_cons};
+    use std::cell::RefCell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::sync::Once;
+
+    static SETUP: Once = Once::new();
+
+    // every test shares the global environment, which is set up once
+    fn setup() {
+        SETUP.call_once(|| unsafe {
+            ENV = setup_environment();
+        });
+    }
+
+    // evaluates a program in the shared global environment
+    fn run(program: &str) -> Exp {
+        let mut tokens = tokenize(&[program.to_string()]);
+        unsafe { eval(build_syntax_tree(&mut tokens), ENV.clone()).unwrap() }
+    }
+
+    // the list of the given integers
+    fn numbers(xs: &[i64]) -> Exp {
+        let mut result = Exp::List(Pair::Nil);
+        for x in xs.iter().rev() {
+            result = scheme_cons(Exp::Integer(*x), result);
+        }
+        result
+    }
+
+    // an output port that keeps what is written to it
+    #[derive(Debug)]
+    pub(crate) struct Recorder(pub(crate) Rc<RefCell<String>>);
+
+    impl Port for Recorder {
+        fn is_output(&self) -> bool {
+            true
+        }
+
+        fn write_str(&mut self, s: &str) {
+            self.0.borrow_mut().push_str(s);
+        }
+    }
+
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_eval_self_evaluating_exp() {
+        setup();
         let x1 = Exp::Integer(1);
         let x2 = Exp::FloatNumber(3.14);
-        let x3 = Exp::Quote("winter".to_string());
+        let x3 = scheme_list!(Exp::Symbol(QUOTE), Exp::Symbol(Symbol::new("winter")));
         let x4 = Exp::SchemeString("WINTER IS COMING!".to_string());
         let x5 = Exp::Bool(true);
         let x6 = Exp::Bool(false);
         unsafe {
-            assert_eq!(x1.clone(), eval(x1, ENV.clone()).unwrap().unwrap());
-            assert_eq!(x2.clone(), eval(x2, ENV.clone()).unwrap().unwrap());
-            assert_eq!(x3.clone(), eval(x3, ENV.clone()).unwrap().unwrap());
-            assert_eq!(x4.clone(), eval(x4, ENV.clone()).unwrap().unwrap());
-            assert_eq!(x5.clone(), eval(x5, ENV.clone()).unwrap().unwrap());
-            assert_eq!(x6.clone(), eval(x6, ENV.clone()).unwrap().unwrap());
+            assert_eq!(x1.clone(), eval(x1, ENV.clone()).unwrap());
+            assert_eq!(x2.clone(), eval(x2, ENV.clone()).unwrap());
+            assert_eq!(
+                Exp::Symbol(Symbol::new("winter")),
+                eval(x3, ENV.clone()).unwrap()
+            );
+            assert_eq!(x4.clone(), eval(x4, ENV.clone()).unwrap());
+            assert_eq!(x5.clone(), eval(x5, ENV.clone()).unwrap());
+            assert_eq!(x6.clone(), eval(x6, ENV.clone()).unwrap());
         }
     }
 
     #[test]
     fn test_eval_single_variable() {
+        setup();
         unsafe {
             eval(
                 scheme_list!(
-                    Exp::Symbol("define".to_string()),
-                    Exp::Symbol("x".to_string()),
+                    Exp::Symbol(Symbol::new("define")),
+                    Exp::Symbol(Symbol::new("x")),
                     Exp::Integer(8)
                 ),
                 ENV.clone(),
-            );
+            )
+            .unwrap();
             assert_eq!(
-                eval(Exp::Symbol("x".to_string()), ENV.clone())
-                    .unwrap()
-                    .unwrap(),
+                eval(Exp::Symbol(Symbol::new("x")), ENV.clone()).unwrap(),
                 Exp::Integer(8)
             );
         }
@@ -593,76 +2020,92 @@ mod test {
 
     #[test]
     fn test_eval_assignment() {
+        setup();
         unsafe {
             eval(
                 scheme_list!(
-                    Exp::Symbol("define".to_string()),
-                    Exp::Symbol("x".to_string()),
+                    Exp::Symbol(Symbol::new("define")),
+                    Exp::Symbol(Symbol::new("x")),
                     Exp::Integer(8)
                 ),
                 ENV.clone(),
-            );
+            )
+            .unwrap();
             let assignment = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(101)
             );
             let x = eval(assignment.clone(), ENV.clone());
             assert_eq!(
-                eval(Exp::Symbol("x".to_string()), ENV.clone())
-                    .unwrap()
-                    .unwrap(),
+                eval(Exp::Symbol(Symbol::new("x")), ENV.clone()).unwrap(),
                 Exp::Integer(101)
             );
         }
     }
 
+    #[test]
+    fn test_eval_error_propagation() {
+        setup();
+        // an error in an operand, or in the value of a definition or an
+        // assignment, is returned to the caller instead of panicking
+        let try_run = |program: &str| {
+            let mut tokens = tokenize(&[program.to_string()]);
+            unsafe { eval(build_syntax_tree(&mut tokens), ENV.clone()) }
+        };
+        run("(define error-target 1)");
+        for program in [
+            "(+ 1 ((quote (1)) 2))",
+            "(define error-target ((quote (1)) 2))",
+            "(set! error-target ((quote (1)) 2))",
+        ] {
+            assert_eq!(try_run(program), Err("unknow procedure type: APPLY"));
+        }
+        assert_eq!(run("error-target"), Exp::Integer(1));
+    }
+
     #[test]
     fn test_eval_definition_single_variable() {
+        setup();
         unsafe {
             eval(
                 scheme_list!(
-                    Exp::Symbol("define".to_string()),
-                    Exp::Symbol("x".to_string()),
+                    Exp::Symbol(Symbol::new("define")),
+                    Exp::Symbol(Symbol::new("x")),
                     Exp::Integer(8)
                 ),
                 ENV.clone(),
-            );
+            )
+            .unwrap();
             let assignment = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(101)
             );
             let x = eval(assignment.clone(), ENV.clone());
             assert_eq!(
-                eval(Exp::Symbol("x".to_string()), ENV.clone())
-                    .unwrap()
-                    .unwrap(),
+                eval(Exp::Symbol(Symbol::new("x")), ENV.clone()).unwrap(),
                 Exp::Integer(101)
             );
 
             let definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(999)
             );
             let s = eval(definition.clone(), ENV.clone());
             let second_definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("y".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("y")),
                 Exp::Integer(333)
             );
             let t = eval(second_definition, ENV.clone());
             assert_eq!(
-                eval(Exp::Symbol("x".to_string()), ENV.clone())
-                    .unwrap()
-                    .unwrap(),
+                eval(Exp::Symbol(Symbol::new("x")), ENV.clone()).unwrap(),
                 Exp::Integer(999)
             );
             assert_eq!(
-                eval(Exp::Symbol("y".to_string()), ENV.clone())
-                    .unwrap()
-                    .unwrap(),
+                eval(Exp::Symbol(Symbol::new("y")), ENV.clone()).unwrap(),
                 Exp::Integer(333)
             );
         }
@@ -670,93 +2113,100 @@ mod test {
 
     #[test]
     fn test_eval_definition_compoud_procedure() {
+        setup();
         unsafe {
             eval(
                 scheme_list!(
-                    Exp::Symbol("define".to_string()),
-                    Exp::Symbol("x".to_string()),
+                    Exp::Symbol(Symbol::new("define")),
+                    Exp::Symbol(Symbol::new("x")),
                     Exp::Integer(8)
                 ),
                 ENV.clone(),
-            );
+            )
+            .unwrap();
             let assignment = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(101)
             );
             let x = eval(assignment.clone(), ENV.clone());
             let definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(999)
             );
             let y = eval(definition, ENV.clone());
             let second_definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("y".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("y")),
                 Exp::Integer(333)
             );
             let z = eval(second_definition, ENV.clone());
             let another_definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
+                Exp::Symbol(Symbol::new("define")),
                 scheme_list!(
-                    Exp::Symbol("square".to_string()),
-                    Exp::Symbol("x".to_string())
+                    Exp::Symbol(Symbol::new("square")),
+                    Exp::Symbol(Symbol::new("x"))
                 ),
                 scheme_list!(
-                    Exp::Symbol("*".to_string()),
-                    Exp::Symbol("x".to_string()),
-                    Exp::Symbol("x".to_string())
+                    Exp::Symbol(Symbol::new("*")),
+                    Exp::Symbol(Symbol::new("x")),
+                    Exp::Symbol(Symbol::new("x"))
                 )
             );
             let s = eval(another_definition.clone(), ENV.clone());
-            let app_exp = scheme_list!(Exp::Symbol("square".to_string()), Exp::Integer(3));
+            let app_exp = scheme_list!(Exp::Symbol(Symbol::new("square")), Exp::Integer(3));
+            assert_eq!(eval(app_exp, ENV.clone()).unwrap(), Exp::Integer(9));
+            let mut tokens = tokenize(&["((quote (procedure (x) (x))) 3)".to_string()]);
+            let quoted = build_syntax_tree(&mut tokens);
             assert_eq!(
-                eval(app_exp, ENV.clone()).unwrap().unwrap(),
-                Exp::FloatNumber(9.0)
+                eval(quoted, ENV.clone()),
+                Err("unknow procedure type: APPLY")
             );
         }
     }
 
     #[test]
     fn test_eval_primitive_procedure() {
+        setup();
         unsafe {
             eval(
                 scheme_list!(
-                    Exp::Symbol("define".to_string()),
-                    Exp::Symbol("x".to_string()),
+                    Exp::Symbol(Symbol::new("define")),
+                    Exp::Symbol(Symbol::new("x")),
                     Exp::Integer(8)
                 ),
                 ENV.clone(),
-            );
+            )
+            .unwrap();
             let assignment = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(101)
             );
             let x = eval(assignment.clone(), ENV.clone());
             let definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("x".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("x")),
                 Exp::Integer(999)
             );
             let y = eval(definition, ENV.clone());
             let second_definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("y".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("y")),
                 Exp::Integer(333)
             );
             let z = eval(second_definition, ENV.clone());
             let another_definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
+                Exp::Symbol(Symbol::new("define")),
                 scheme_list!(
-                    Exp::Symbol("square".to_string()),
-                    Exp::Symbol("x".to_string())
+                    Exp::Symbol(Symbol::new("square")),
+                    Exp::Symbol(Symbol::new("x"))
                 ),
                 scheme_list!(
-                    Exp::Symbol("*".to_string()),
-                    Exp::Symbol("x".to_string()),
-                    Exp::Symbol("x".to_string())
+                    Exp::Symbol(Symbol::new("*")),
+                    Exp::Symbol(Symbol::new("x")),
+                    Exp::Symbol(Symbol::new("x"))
                 )
             );
             let t = eval(another_definition.clone(), ENV.clone());
@@ -766,79 +2216,83 @@ mod test {
                 Exp::Integer(5)
             );
             let s_definition = scheme_list!(
-                Exp::Symbol("define".to_string()),
-                Exp::Symbol("s".to_string()),
+                Exp::Symbol(Symbol::new("define")),
+                Exp::Symbol(Symbol::new("s")),
                 s
             );
             let q = eval(s_definition.clone(), ENV.clone());
-            let car_exp =
-                scheme_list!(Exp::Symbol("car".to_string()), Exp::Symbol("s".to_string()));
+            let car_exp = scheme_list!(
+                Exp::Symbol(Symbol::new("car")),
+                Exp::Symbol(Symbol::new("s"))
+            );
             assert_eq!(
-                eval(car_exp, ENV.clone()).unwrap().unwrap(),
+                eval(car_exp, ENV.clone()).unwrap(),
                 scheme_list!(Exp::Integer(1), Exp::Integer(2))
             );
-            let cdr_exp =
-                scheme_list!(Exp::Symbol("cdr".to_string()), Exp::Symbol("s".to_string()));
+            let cdr_exp = scheme_list!(
+                Exp::Symbol(Symbol::new("cdr")),
+                Exp::Symbol(Symbol::new("s"))
+            );
             assert_eq!(
-                eval(cdr_exp, ENV.clone()).unwrap().unwrap(),
+                eval(cdr_exp, ENV.clone()).unwrap(),
                 scheme_list!(
                     scheme_list!(Exp::Integer(3), Exp::Integer(4)),
                     Exp::Integer(5)
                 )
             );
-            let null_exp = scheme_list!(Exp::Symbol("null?".to_string()), Exp::List(Pair::Nil));
+            let null_exp = scheme_list!(Exp::Symbol(Symbol::new("null?")), Exp::List(Pair::Nil));
             assert_eq!(
-                eval(null_exp.clone(), ENV.clone()).unwrap().unwrap(),
+                eval(null_exp.clone(), ENV.clone()).unwrap(),
                 Exp::Bool(true)
             );
 
             let add_exp = scheme_list!(
-                Exp::Symbol("+".to_string()),
+                Exp::Symbol(Symbol::new("+")),
                 Exp::FloatNumber(3.15),
                 Exp::FloatNumber(1.85)
             );
             assert_eq!(
-                eval(add_exp.clone(), ENV.clone()).unwrap().unwrap(),
+                eval(add_exp.clone(), ENV.clone()).unwrap(),
                 Exp::FloatNumber(5.0)
             );
 
             let substract_exp = scheme_list!(
-                Exp::Symbol("-".to_string()),
+                Exp::Symbol(Symbol::new("-")),
                 Exp::Integer(8),
                 Exp::FloatNumber(2.5)
             );
             assert_eq!(
-                eval(substract_exp.clone(), ENV.clone()).unwrap().unwrap(),
+                eval(substract_exp.clone(), ENV.clone()).unwrap(),
                 Exp::FloatNumber(5.5)
             );
 
             let multiply_exp = scheme_list!(
-                Exp::Symbol("*".to_string()),
+                Exp::Symbol(Symbol::new("*")),
                 Exp::FloatNumber(2.5),
                 Exp::FloatNumber(2.5)
             );
             assert_eq!(
-                eval(multiply_exp.clone(), ENV.clone()).unwrap().unwrap(),
+                eval(multiply_exp.clone(), ENV.clone()).unwrap(),
                 Exp::FloatNumber(6.25)
             );
 
             let divide_exp = scheme_list!(
-                Exp::Symbol("/".to_string()),
+                Exp::Symbol(Symbol::new("/")),
                 Exp::FloatNumber(25.0),
                 Exp::FloatNumber(2.5)
             );
             assert_eq!(
-                eval(divide_exp.clone(), ENV.clone()).unwrap().unwrap(),
+                eval(divide_exp.clone(), ENV.clone()).unwrap(),
                 Exp::FloatNumber(10.0)
             );
 
             let cons_exp = scheme_list!(
-                Exp::Symbol("cons".to_string()),
+                Exp::Symbol(Symbol::new("cons")),
                 scheme_list!(Exp::Integer(1), Exp::Integer(2)),
                 scheme_list!(Exp::Integer(3), Exp::Integer(4))
             );
             assert_eq!(
-                eval(cons_exp.clone(), ENV.clone()).unwrap().unwrap(),
+                eval(cons_exp.clone(), ENV.clone()).unwrap(),
                 scheme_list!(
                     scheme_list!(Exp::Integer(1), Exp::Integer(2)),
                     Exp::Integer(3),
@@ -847,4 +2301,723 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_eval_exact_rational() {
+        setup();
+        unsafe {
+            let divide_exp = scheme_list!(
+                Exp::Symbol(Symbol::new("/")),
+                Exp::Integer(6),
+                Exp::Integer(3)
+            );
+            assert_eq!(eval(divide_exp, ENV.clone()).unwrap(), Exp::Integer(2));
+            let third_exp = scheme_list!(
+                Exp::Symbol(Symbol::new("/")),
+                Exp::Integer(1),
+                Exp::Integer(3)
+            );
+            assert_eq!(
+                eval(third_exp.clone(), ENV.clone()).unwrap(),
+                Exp::Rational(1, 3)
+            );
+            let add_exp = scheme_list!(
+                Exp::Symbol(Symbol::new("+")),
+                third_exp.clone(),
+                Exp::Rational(1, 6)
+            );
+            assert_eq!(eval(add_exp, ENV.clone()).unwrap(), Exp::Rational(1, 2));
+            let inexact_exp = scheme_list!(Exp::Symbol(Symbol::new("exact->inexact")), third_exp);
+            assert_eq!(
+                eval(inexact_exp, ENV.clone()).unwrap(),
+                Exp::FloatNumber(1.0 / 3.0)
+            );
+            let exact_exp = scheme_list!(
+                Exp::Symbol(Symbol::new("inexact->exact")),
+                Exp::FloatNumber(0.5)
+            );
+            assert_eq!(eval(exact_exp, ENV.clone()).unwrap(), Exp::Rational(1, 2));
+        }
+        let run = |program: &str| exp_to_string(&run(program), false);
+        assert_eq!(run("(+ 1/3 9223372036854775807)"), "27670116110564327422/3");
+        assert_eq!(
+            run("(- (+ 1/3 9223372036854775807) 9223372036854775807)"),
+            "1/3"
+        );
+        assert_eq!(run("(* 3 100000000000000000000/6)"), "50000000000000000000");
+        assert_eq!(
+            run("(quotient -9223372036854775808 -1)"),
+            "9223372036854775808"
+        );
+    }
+
+    #[test]
+    fn test_eval_variadic_arithmetic() {
+        setup();
+        let plus = Exp::Symbol(Symbol::new("+"));
+        let minus = Exp::Symbol(Symbol::new("-"));
+        let multiply = Exp::Symbol(Symbol::new("*"));
+        let divide = Exp::Symbol(Symbol::new("/"));
+        unsafe {
+            assert_eq!(
+                eval(scheme_list!(plus.clone()), ENV.clone()).unwrap(),
+                Exp::Integer(0)
+            );
+            assert_eq!(
+                eval(scheme_list!(multiply.clone()), ENV.clone()).unwrap(),
+                Exp::Integer(1)
+            );
+            assert_eq!(
+                eval(scheme_list!(minus.clone(), Exp::Integer(5)), ENV.clone()).unwrap(),
+                Exp::Integer(-5)
+            );
+            assert_eq!(
+                eval(scheme_list!(divide.clone(), Exp::Integer(2)), ENV.clone()).unwrap(),
+                Exp::Rational(1, 2)
+            );
+            let sum = scheme_list!(
+                plus,
+                Exp::Integer(1),
+                Exp::Integer(2),
+                Exp::Integer(3),
+                Exp::FloatNumber(0.5)
+            );
+            assert_eq!(eval(sum, ENV.clone()).unwrap(), Exp::FloatNumber(6.5));
+            let difference =
+                scheme_list!(minus, Exp::Integer(10), Exp::Integer(1), Exp::Integer(2));
+            assert_eq!(eval(difference, ENV.clone()).unwrap(), Exp::Integer(7));
+            let quotient = scheme_list!(divide, Exp::Integer(60), Exp::Integer(2), Exp::Integer(3));
+            assert_eq!(eval(quotient, ENV.clone()).unwrap(), Exp::Integer(10));
+        }
+    }
+
+    #[test]
+    fn test_eval_comparison() {
+        setup();
+        let compare = |operator: &str, x: Exp, y: Exp, z: Exp| unsafe {
+            let exp = scheme_list!(Exp::Symbol(Symbol::new(operator)), x, y, z);
+            eval(exp, ENV.clone()).unwrap()
+        };
+        let (one, two, three) = (Exp::Integer(1), Exp::Integer(2), Exp::Integer(3));
+        assert_eq!(
+            compare("<", one.clone(), two.clone(), three.clone()),
+            Exp::Bool(true)
+        );
+        assert_eq!(
+            compare("<", one.clone(), two.clone(), two.clone()),
+            Exp::Bool(false)
+        );
+        assert_eq!(
+            compare("<=", one.clone(), two.clone(), two.clone()),
+            Exp::Bool(true)
+        );
+        assert_eq!(
+            compare(">", three.clone(), two.clone(), two.clone()),
+            Exp::Bool(false)
+        );
+        assert_eq!(
+            compare(">=", three.clone(), two.clone(), two.clone()),
+            Exp::Bool(true)
+        );
+        assert_eq!(
+            compare("<", one.clone(), Exp::FloatNumber(f64::NAN), three.clone()),
+            Exp::Bool(false)
+        );
+        assert_eq!(compare("=", two.clone(), two.clone(), two), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_equivalence() {
+        setup();
+        assert_eq!(run("(eq? 2 2)"), Exp::Bool(true));
+        assert_eq!(run("(eq? 2.0 2.0)"), Exp::Bool(false));
+        assert_eq!(run("(eqv? 2.0 2.0)"), Exp::Bool(true));
+        assert_eq!(run("(eqv? 2 2.0)"), Exp::Bool(false));
+        assert_eq!(run("(= 2 2.0 4/2)"), Exp::Bool(true));
+        assert_eq!(run("(= 1+i 1.0+1.0i)"), Exp::Bool(true));
+        assert_eq!(run("(eq? (cons 1 Nil) (cons 1 Nil))"), Exp::Bool(false));
+        assert_eq!(run("(equal? (cons 1 Nil) (cons 1 Nil))"), Exp::Bool(true));
+        assert_eq!(run("(eq? #\\a #\\a)"), Exp::Bool(true));
+        assert_eq!(
+            run("(memv 2.0 (cons 1.0 (cons 2.0 (cons 3.0 Nil))))"),
+            scheme_list!(Exp::FloatNumber(2.0), Exp::FloatNumber(3.0))
+        );
+        assert_eq!(run("(memq 4 (cons 1 (cons 2 Nil)))"), Exp::Bool(false));
+        assert_eq!(
+            run("(member (cons 2 Nil) (cons (cons 1 Nil) (cons (cons 2 Nil) Nil)))"),
+            scheme_list!(scheme_list!(Exp::Integer(2)))
+        );
+        assert_eq!(
+            run("(assv 2 (cons (cons 1 10) (cons (cons 2 20) Nil)))"),
+            scheme_list!(Exp::Integer(2), Exp::Integer(20))
+        );
+        assert_eq!(
+            run("(assoc 3 (cons (cons 1 10) (cons (cons 2 20) Nil)))"),
+            Exp::Bool(false)
+        );
+    }
+
+    #[test]
+    fn test_eval_symbol() {
+        setup();
+        assert_eq!(run("'winter"), Exp::Symbol(Symbol::new("winter")));
+        assert_eq!(run("(symbol? 'winter)"), Exp::Bool(true));
+        assert_eq!(run("(symbol? \"winter\")"), Exp::Bool(false));
+        assert_eq!(
+            run("(symbol->string 'winter)"),
+            Exp::SchemeString("winter".to_string())
+        );
+        assert_eq!(
+            run("(eq? (string->symbol \"winter\") 'winter)"),
+            Exp::Bool(true)
+        );
+        assert_eq!(
+            run("(memq 'c '(a b c d))"),
+            scheme_list!(Exp::Symbol(Symbol::new("c")), Exp::Symbol(Symbol::new("d")))
+        );
+    }
+
+    #[test]
+    fn test_eval_list_library() {
+        setup();
+        assert_eq!(run("(list 1 2 3)"), numbers(&[1, 2, 3]));
+        assert_eq!(run("(length '(1 2 3))"), Exp::Integer(3));
+        assert_eq!(run("(append '(1) '(2 3) '() '(4))"), numbers(&[1, 2, 3, 4]));
+        assert_eq!(run("(reverse '(1 2 3))"), numbers(&[3, 2, 1]));
+        assert_eq!(run("(list-ref '(1 2 3) 1)"), Exp::Integer(2));
+        assert_eq!(run("(list-tail '(1 2 3) 2)"), numbers(&[3]));
+        assert_eq!(run("(last-pair '(1 2 3))"), numbers(&[3]));
+        assert_eq!(
+            run("(filter (lambda (x) (> x 1)) '(1 2 3))"),
+            numbers(&[2, 3])
+        );
+        assert_eq!(run("(remove (lambda (x) (> x 1)) '(1 2 3))"), numbers(&[1]));
+        assert_eq!(run("(reduce + 0 '(1 2 3 4))"), Exp::Integer(10));
+        assert_eq!(run("(reduce + 0 '())"), Exp::Integer(0));
+        assert_eq!(run("(fold-left - 0 '(1 2 3))"), Exp::Integer(-6));
+        assert_eq!(run("(fold-right - 0 '(1 2 3))"), Exp::Integer(2));
+        assert_eq!(run("(delete 2 '(1 2 3 2))"), numbers(&[1, 3]));
+        assert_eq!(run("(iota 3)"), numbers(&[0, 1, 2]));
+        assert_eq!(run("(iota 3 1 2)"), numbers(&[1, 3, 5]));
+        assert_eq!(run("(any (lambda (x) (> x 2)) '(1 2 3))"), Exp::Bool(true));
+        assert_eq!(
+            run("(every (lambda (x) (> x 2)) '(1 2 3))"),
+            Exp::Bool(false)
+        );
+        assert_eq!(run("(every (lambda (x) (> x 2)) '())"), Exp::Bool(true));
+        assert_eq!(run("(list-copy '(1 2))"), numbers(&[1, 2]));
+        assert_eq!(run("(pair? '())"), Exp::Bool(false));
+        assert_eq!(run("(list? '())"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_first_class_primitives() {
+        setup();
+        assert_eq!(run("+"), Exp::Primitive(Symbol::new("+")));
+        run("(define plus +)");
+        assert_eq!(run("(plus 1 2 3)"), Exp::Integer(6));
+        run("(define (first-of list) (car list))");
+        run("(define (max-of x y) (if (> x y) x y))");
+        assert_eq!(run("(first-of '(7 8))"), Exp::Integer(7));
+        assert_eq!(run("(fold-left max-of 0 '(3 9 2))"), Exp::Integer(9));
+    }
+
+    #[test]
+    fn test_eval_higher_order_primitives() {
+        setup();
+        assert_eq!(run("(apply + 1 2 '(3 4))"), Exp::Integer(10));
+        assert_eq!(run("(map + '(1 2 3) '(10 20))"), numbers(&[11, 22]));
+        assert_eq!(
+            run("(map (lambda (x) (* x x)) '(1 2 3))"),
+            numbers(&[1, 4, 9])
+        );
+        assert_eq!(run("(for-each car '((1) (2)))"), Exp::Unspecified);
+        assert_eq!(
+            run("(vector-map + #(1 2) #(10 20 30))"),
+            Exp::Vector(Rc::new(vec![Exp::Integer(11), Exp::Integer(22)]))
+        );
+        assert_eq!(run("(sort '(3 1 2 1) <)"), numbers(&[1, 1, 2, 3]));
+        assert_eq!(
+            run("(sort (vector 3 1 2) >)"),
+            Exp::Vector(Rc::new(vec![
+                Exp::Integer(3),
+                Exp::Integer(2),
+                Exp::Integer(1)
+            ]))
+        );
+        // tail calls, through apply as well, run in constant stack space
+        run("(define (count-down n) (if (= n 0) 'done (count-down (- n 1))))");
+        assert_eq!(run("(count-down 10000)"), Exp::Symbol(Symbol::new("done")));
+        run("(define (apply-down n) (if (= n 0) 'done (apply apply-down (list (- n 1)))))");
+        assert_eq!(run("(apply-down 10000)"), Exp::Symbol(Symbol::new("done")));
+        // arity is checked before the arguments are taken apart, and an
+        // error from a procedure called back keeps its message
+        let message = |program: &str| {
+            let payload = panic::catch_unwind(AssertUnwindSafe(|| run(program))).unwrap_err();
+            match payload.downcast_ref::<&str>() {
+                Some(x) => x.to_string(),
+                None => payload.downcast_ref::<String>().unwrap().clone(),
+            }
+        };
+        assert_eq!(message("(with-output-to-string)"), "wrong number of args!");
+        assert_eq!(
+            message("(call-with-input-file \"x\")"),
+            "wrong number of args!"
+        );
+        assert_eq!(
+            message("(map (make-parameter 1) '(2))"),
+            "a parameter takes no arguments: APPLY"
+        );
+    }
+
+    #[test]
+    fn test_eval_record_type() {
+        setup();
+        run("(define-record-type <account> (make-account owner) account? (owner account-owner) (balance account-balance set-account-balance!))");
+        run("(define acc (make-account 'yi))");
+        assert_eq!(run("(account? acc)"), Exp::Bool(true));
+        assert_eq!(run("(account? '(account yi))"), Exp::Bool(false));
+        assert_eq!(run("(account-owner acc)"), Exp::Symbol(Symbol::new("yi")));
+        assert_eq!(run("(account-balance acc)"), Exp::Bool(false));
+        run("(set-account-balance! acc 100)");
+        assert_eq!(run("(account-balance acc)"), Exp::Integer(100));
+        assert_eq!(run("(eq? acc acc)"), Exp::Bool(true));
+        assert_eq!(run("(equal? acc (make-account 'yi))"), Exp::Bool(false));
+    }
+
+    #[test]
+    fn test_eval_parameterize() {
+        setup();
+        run("(define indent (make-parameter 2 (lambda (x) (* x 2))))");
+        run("(define (current-indent) (indent))");
+        assert_eq!(run("(indent)"), Exp::Integer(4));
+        assert_eq!(
+            run("(parameterize ((indent 3)) (current-indent))"),
+            Exp::Integer(6)
+        );
+        assert_eq!(run("(indent)"), Exp::Integer(4));
+        // the binding is undone when the body fails as well
+        let failed = panic::catch_unwind(AssertUnwindSafe(|| {
+            run("(parameterize ((indent 5)) (car '()))")
+        }));
+        assert!(failed.is_err());
+        assert_eq!(run("(indent)"), Exp::Integer(4));
+        // a parameter called with an argument is an error of eval
+        let mut tokens = tokenize(&["(indent 1)".to_string()]);
+        let result = unsafe { eval(build_syntax_tree(&mut tokens), ENV.clone()) };
+        assert_eq!(result, Err("a parameter takes no arguments: APPLY"));
+    }
+
+    #[test]
+    fn test_eval_bytevector() {
+        setup();
+        let bytes = |x: &[u8]| Exp::Bytevector(Rc::new(RefCell::new(x.to_vec())));
+        assert_eq!(run("#u8(1 2 255)"), bytes(&[1, 2, 255]));
+        assert_eq!(run("(make-bytevector 2 7)"), bytes(&[7, 7]));
+        assert_eq!(run("(bytevector-length #u8(1 2 3))"), Exp::Integer(3));
+        assert_eq!(run("(bytevector-u8-ref #u8(1 2 3) 1)"), Exp::Integer(2));
+        run("(define bv (bytevector 1 2 3))");
+        run("(bytevector-u8-set! bv 0 9)");
+        assert_eq!(run("bv"), bytes(&[9, 2, 3]));
+        assert_eq!(run("(bytevector-copy bv 1)"), bytes(&[2, 3]));
+        assert_eq!(run("(bytevector-copy bv 0 1)"), bytes(&[9]));
+        assert_eq!(
+            run("(bytevector-append #u8(1) #u8() #u8(2 3))"),
+            bytes(&[1, 2, 3])
+        );
+        assert_eq!(run("(string->utf8 \"λa\")"), bytes(&[206, 187, 97]));
+        assert_eq!(
+            run("(utf8->string #u8(104 105 33) 0 2)"),
+            Exp::SchemeString("hi".to_string())
+        );
+        assert_eq!(run("(equal? #u8(1 2) (bytevector 1 2))"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_output_procedures() {
+        setup();
+        let output = Rc::new(RefCell::new(String::new()));
+        set_current_output_port(Recorder(output.clone()));
+        // the global environment may have been set up by another test
+        // thread, which has its own current output port
+        unsafe {
+            let port = current_output_port().value.borrow().clone();
+            ENV = define_variable(Exp::Symbol(Symbol::new("recorder")), port, ENV.clone());
+        }
+        run("(display \"a\\\"b\")");
+        run("(write \"a\\\"b\")");
+        run("(newline)");
+        run("(write (list #\\x #\\space 1.5 'sym))");
+        run("(write-char #\\z)");
+        run("(write-string \"hello\" recorder 1 3)");
+        assert_eq!(
+            *output.borrow(),
+            "a\"b\"a\\\"b\"\n(#\\x #\\space 1.5 sym)zel"
+        );
+        assert_eq!(run("(output-port? (current-error-port))"), Exp::Bool(true));
+        assert_eq!(run("(input-port? (current-output-port))"), Exp::Bool(false));
+    }
+
+    #[test]
+    fn test_eval_string_ports() {
+        setup();
+        assert_eq!(
+            run("(with-output-to-string (lambda () (display 1) (write \"a\")))"),
+            Exp::SchemeString("1\"a\"".to_string())
+        );
+        assert_eq!(
+            run("(call-with-output-string (lambda (p) (write-char #\\x p) (display 'y p)))"),
+            Exp::SchemeString("xy".to_string())
+        );
+        run("(define out (open-output-string))");
+        run("(write-string \"ab\" out)");
+        run("(display 12 out)");
+        assert_eq!(
+            run("(get-output-string out)"),
+            Exp::SchemeString("ab12".to_string())
+        );
+        run("(define in (open-input-string \"xy\nlast\"))");
+        assert_eq!(run("(peek-char in)"), Exp::Character('x'));
+        assert_eq!(run("(read-char in)"), Exp::Character('x'));
+        assert_eq!(run("(read-line in)"), Exp::SchemeString("y".to_string()));
+        assert_eq!(
+            run("(read-string 10 in)"),
+            Exp::SchemeString("last".to_string())
+        );
+        assert_eq!(run("(eof-object? (read-char in))"), Exp::Bool(true));
+        assert_eq!(run("(eof-object? (read-line in))"), Exp::Bool(true));
+        assert_eq!(run("(eof-object? (eof-object))"), Exp::Bool(true));
+        assert_eq!(run("(input-port? in)"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_file_ports() {
+        setup();
+        let path = std::env::temp_dir().join("ruscheme_test_eval_file_ports.txt");
+        let name = path.to_str().unwrap();
+        run(&format!(
+            "(call-with-output-file \"{}\" (lambda (p) (write-string \"one\" p) (newline p)))",
+            name
+        ));
+        run(&format!("(define in (open-input-file \"{}\"))", name));
+        assert_eq!(run("(char-ready? in)"), Exp::Bool(true));
+        assert_eq!(run("(read-line in)"), Exp::SchemeString("one".to_string()));
+        assert_eq!(run("(eof-object? (peek-char in))"), Exp::Bool(true));
+        run("(close-port in)");
+        run(&format!(
+            "(with-output-to-file \"{}\" (lambda () (display \"two\")))",
+            name
+        ));
+        assert_eq!(
+            run(&format!(
+                "(call-with-input-file \"{}\" (lambda (p) (read-string 10 p)))",
+                name
+            )),
+            Exp::SchemeString("two".to_string())
+        );
+        // the port is closed when the procedure fails as well
+        let failed = panic::catch_unwind(AssertUnwindSafe(|| {
+            run(&format!(
+                "(call-with-output-file \"{}\" (lambda (p) (display \"three\" p) (car '())))",
+                name
+            ))
+        }));
+        assert!(failed.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "three");
+        // a port left open is reached by flush_output_ports
+        run(&format!("(define out (open-output-file \"{}\"))", name));
+        run("(display \"four\" out)");
+        flush_output_ports();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "four");
+        run("(close-port out)");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_eval_load_and_include() {
+        setup();
+        let path = std::env::temp_dir().join("ruscheme_test_eval_load_and_include.scm");
+        let name = path.to_str().unwrap();
+        std::fs::write(
+            &path,
+            "(define (loaded-square x) (* x x))\n; a comment\n(define loaded-ten 10)\n",
+        )
+        .unwrap();
+        run(&format!("(load \"{}\")", name));
+        assert_eq!(run("(loaded-square loaded-ten)"), Exp::Integer(100));
+        assert_eq!(
+            run(&format!("(begin (include \"{}\") (loaded-square 3))", name)),
+            Exp::Integer(9)
+        );
+        std::fs::remove_file(path).unwrap();
+
+        // relative names are taken from the directory of the loading file
+        let dir = std::env::temp_dir().join("ruscheme_test_eval_load_relative");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("main.scm"),
+            "(include \"part.scm\")\n(load \"other.scm\")\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("part.scm"), "(define relative-part 1)\n").unwrap();
+        std::fs::write(dir.join("other.scm"), "(define relative-other 2)\n").unwrap();
+        run(&format!(
+            "(load \"{}\")",
+            dir.join("main.scm").to_str().unwrap()
+        ));
+        assert_eq!(run("(+ relative-part relative-other)"), Exp::Integer(3));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_eval_library() {
+        setup();
+        run("(define-library (test counter)
+               (export make-counter-list (rename counter-step step))
+               (import (scheme base))
+               (begin
+                 (define counter-step 2)
+                 (define (counter-helper x) (+ x counter-step))
+                 (define (make-counter-list n)
+                   (if (= n 0) '() (cons (counter-helper n) (make-counter-list (- n 1)))))))");
+        run("(import (prefix (only (test counter) make-counter-list) lib:) (rename (test counter) (step lib-step)))");
+        assert_eq!(
+            run("(lib:make-counter-list 2)"),
+            scheme_list!(Exp::Integer(4), Exp::Integer(3))
+        );
+        assert_eq!(run("lib-step"), Exp::Integer(2));
+        // the definitions of a library are its own
+        let failed = panic::catch_unwind(AssertUnwindSafe(|| run("(counter-helper 1)")));
+        assert!(failed.is_err());
+        run("(import (except (scheme inexact) exp log))");
+        assert_eq!(run("(sqrt 16)"), Exp::Integer(4));
+        // only, except and rename cannot name what the library lacks
+        for program in [
+            "(import (only (test counter) missing))",
+            "(import (except (test counter) missing))",
+            "(import (rename (test counter) (missing other)))",
+        ] {
+            let failed = panic::catch_unwind(AssertUnwindSafe(|| run(program)));
+            assert!(failed.is_err());
+        }
+        run("(import (scheme base))");
+        // the character and cxr libraries are not provided
+        for program in ["(import (scheme char))", "(import (scheme cxr))"] {
+            let failed = panic::catch_unwind(AssertUnwindSafe(|| run(program)));
+            assert!(failed.is_err());
+        }
+
+        // a library that is not defined yet is loaded from the search path
+        let dir = std::env::temp_dir().join("ruscheme_test_eval_library");
+        std::fs::create_dir_all(dir.join("test")).unwrap();
+        std::fs::write(
+            dir.join("test").join("greeting.sld"),
+            "(define-library (test greeting) (export greeting) (import (scheme base)) (include \"greeting.scm\"))",
+        )
+        .unwrap();
+        // the include of a library is next to its .sld file
+        std::fs::write(
+            dir.join("test").join("greeting.scm"),
+            "(define greeting \"hi\")",
+        )
+        .unwrap();
+        library_search_path()
+            .value
+            .replace(scheme_list!(Exp::SchemeString(
+                dir.to_str().unwrap().to_string()
+            )));
+        run("(import (test greeting))");
+        assert_eq!(run("greeting"), Exp::SchemeString("hi".to_string()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_eval_environment() {
+        setup();
+        assert_eq!(
+            run("(eval '(* 2 3) system-global-environment)"),
+            Exp::Integer(6)
+        );
+        run("(eval '(define evaluated-seven 7) (interaction-environment))");
+        assert_eq!(run("evaluated-seven"), Exp::Integer(7));
+        run("(define (environment-with x) (the-environment))");
+        assert_eq!(run("(eval '(+ x 1) (environment-with 5))"), Exp::Integer(6));
+        assert_eq!(run("(environment? (environment-with 5))"), Exp::Bool(true));
+        assert_eq!(run("(environment? 5)"), Exp::Bool(false));
+        // a definition in a captured environment stays there
+        run("(define (captured-environment) (the-environment))");
+        run("(define captured (captured-environment))");
+        run("(define defined-later 7)");
+        run("(eval '(define captured-w 5) captured)");
+        assert_eq!(run("defined-later"), Exp::Integer(7));
+        assert_eq!(run("(eval 'captured-w captured)"), Exp::Integer(5));
+        run("(eval '(set! captured-w 6) captured)");
+        assert_eq!(run("(eval 'captured-w captured)"), Exp::Integer(6));
+        let failed = panic::catch_unwind(AssertUnwindSafe(|| run("captured-w")));
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn test_eval_unspecified() {
+        setup();
+        let output = Rc::new(RefCell::new(String::new()));
+        set_current_output_port(Recorder(output.clone()));
+        assert_eq!(
+            run("(list (display 1) (newline))"),
+            scheme_list!(Exp::Unspecified, Exp::Unspecified)
+        );
+        assert_eq!(*output.borrow(), "1\n");
+        assert_eq!(run("(define unspecified-one 1)"), Exp::Unspecified);
+        assert_eq!(run("(if #f 1)"), Exp::Unspecified);
+        assert_eq!(run("(if #t 1)"), Exp::Integer(1));
+    }
+
+    #[test]
+    fn test_eval_read() {
+        setup();
+        run(
+            "(define data (open-input-string \"(a \\\"b)\\\" #\\\\( 1) ; comment\n 'x #(1 2) sym\"))",
+        );
+        assert_eq!(
+            run("(read data)"),
+            scheme_list!(
+                Exp::Symbol(Symbol::new("a")),
+                Exp::SchemeString("b)".to_string()),
+                Exp::Character('('),
+                Exp::Integer(1)
+            )
+        );
+        assert_eq!(
+            run("(read data)"),
+            scheme_list!(
+                Exp::Symbol(Symbol::new("quote")),
+                Exp::Symbol(Symbol::new("x"))
+            )
+        );
+        assert_eq!(run("(vector-length (read data))"), Exp::Integer(2));
+        assert_eq!(run("(read data)"), Exp::Symbol(Symbol::new("sym")));
+        assert_eq!(run("(eof-object? (read data))"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_math_library() {
+        setup();
+        assert_eq!(run("(square 1/2)"), Exp::Rational(1, 4));
+        assert_eq!(run("(log 8 2)"), Exp::FloatNumber(3.0));
+        assert_eq!(
+            run("(atan 1 0)"),
+            Exp::FloatNumber(std::f64::consts::FRAC_PI_2)
+        );
+        assert_eq!(
+            run("(floor/ -7 2)"),
+            scheme_list!(Exp::Integer(-4), Exp::Integer(1))
+        );
+        assert_eq!(
+            run("(truncate/ -7 2)"),
+            scheme_list!(Exp::Integer(-3), Exp::Integer(-1))
+        );
+        assert_eq!(run("(gcd 12 -18 27)"), Exp::Integer(3));
+        assert_eq!(run("(lcm)"), Exp::Integer(1));
+        assert_eq!(run("(max 1 3 2)"), Exp::Integer(3));
+        assert_eq!(run("(min 1 2.0)"), Exp::FloatNumber(1.0));
+        assert_eq!(run("(zero? 0.0)"), Exp::Bool(true));
+        assert_eq!(run("(negative? -1/2)"), Exp::Bool(true));
+        assert_eq!(run("(odd? 7)"), Exp::Bool(true));
+        assert_eq!(run("(even? 7)"), Exp::Bool(false));
+        assert_eq!(run("(exact? 1/2)"), Exp::Bool(true));
+        assert_eq!(run("(inexact? 0.5)"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_bitwise_operations() {
+        setup();
+        let run = |program: &str| exp_to_string(&run(program), true);
+        assert_eq!(run("(bitwise-and 12 10 -1)"), "8");
+        assert_eq!(run("(bitwise-or)"), "0");
+        assert_eq!(run("(bitwise-xor 12 10)"), "6");
+        assert_eq!(run("(bitwise-not -1)"), "0");
+        assert_eq!(
+            run("(arithmetic-shift 1 100)"),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(run("(arithmetic-shift -5 -1)"), "-3");
+        assert_eq!(
+            run("(bitwise-and (expt 2 70) -1)"),
+            "1180591620717411303424"
+        );
+        assert_eq!(run("(- (bitwise-or (expt 2 70) 1) (expt 2 70))"), "1");
+        assert_eq!(run("(bit-count -1)"), "0");
+        assert_eq!(run("(bit-count (- (expt 2 80) 1))"), "80");
+        assert_eq!(run("(integer-length 255)"), "8");
+        assert_eq!(run("(bit-set? 100 (arithmetic-shift 1 100))"), "#t");
+        assert_eq!(run("(bit-set? 1 5)"), "#f");
+        assert_eq!(run("(gcd (expt 2 70) 6)"), "2");
+        assert_eq!(run("(lcm (expt 2 70) 3)"), "3541774862152233910272");
+        assert_eq!(
+            run("(exact-integer-sqrt (expt 10 40))"),
+            "(100000000000000000000 0)"
+        );
+        assert_eq!(
+            run("(* 99999999999 99999999999 99999999999)"),
+            "999999999970000000000299999999999"
+        );
+        assert_eq!(
+            run("(quotient 100000000000000000000 3)"),
+            "33333333333333333333"
+        );
+    }
+
+    #[test]
+    fn test_eval_random() {
+        setup();
+        assert_eq!(run("(random-seed! 2024)"), Exp::Unspecified);
+        let first = run("(list (random 100) (random 100) (random 1.0))");
+        run("(random-seed! 2024)");
+        assert_eq!(run("(list (random 100) (random 100) (random 1.0))"), first);
+        assert_eq!(run("(random 1)"), Exp::Integer(0));
+        assert_eq!(run("(exact? (random (expt 10 30)))"), Exp::Bool(true));
+        assert_eq!(
+            run("(< (random (expt 10 30)) (expt 10 30))"),
+            Exp::Bool(true)
+        );
+        assert_eq!(run("(inexact? (random 10.0))"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_time() {
+        setup();
+        let output = Rc::new(RefCell::new(String::new()));
+        set_current_output_port(Recorder(output.clone()));
+        assert_eq!(run("(time (+ 1 2))"), Exp::Integer(3));
+        assert!(output.borrow().starts_with("cpu time: "));
+        assert!(output.borrow().contains(", real time: "));
+        assert_eq!(run("(jiffies-per-second)"), Exp::Integer(1_000_000));
+        assert_eq!(run("(<= (current-jiffy) (current-jiffy))"), Exp::Bool(true));
+        assert_eq!(run("(<= (runtime) (runtime))"), Exp::Bool(true));
+        assert_eq!(
+            run("(< (- (current-time) (current-second)) 1)"),
+            Exp::Bool(true)
+        );
+        assert_eq!(run("(inexact? (real-time))"), Exp::Bool(true));
+    }
+
+    #[test]
+    fn test_eval_process_context() {
+        setup();
+        assert_eq!(run("(pair? (command-line))"), Exp::Bool(true));
+        // cargo sets it for the test binary
+        assert_eq!(
+            run("(get-environment-variable \"CARGO_PKG_NAME\")"),
+            Exp::SchemeString("ruscheme".to_string())
+        );
+        assert_eq!(
+            run("(car (cdr (assoc \"CARGO_PKG_NAME\" (get-environment-variables))))"),
+            Exp::SchemeString("ruscheme".to_string())
+        );
+        let code = run_until_exit(|| {
+            run("(define (exit-with-two) (exit 2))");
+            run("(exit-with-two)");
+        });
+        assert_eq!(code, 2);
+    }
 }
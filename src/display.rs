@@ -1,52 +1,102 @@
+#[allow(clippy::module_inception)]
 pub mod display {
     use crate::core_of_interpreter::core_of_interpreter::{Exp, Pair};
+    use crate::number::number::number_to_string;
+    use crate::record::record::record_to_string;
+    use crate::symbol::symbol::Symbol;
     use crate::tool::tools::scheme_cons;
 
+    /// The printed form of exp. Strings and characters are printed as
+    /// they are by display, and as they are read by write.
     #[allow(dead_code)]
-    pub fn print(exp: Exp) {
+    pub fn exp_to_string(exp: &Exp, write: bool) -> String {
         match exp {
-            Exp::FloatNumber(x) => print!("{}", x),
-            Exp::Integer(x) => print!("{}", x),
-            Exp::Symbol(x) => print!("{}", x),
-            Exp::Quote(x) => {
-                print!("{}", &x[1..x.len()]);
+            Exp::FloatNumber(_)
+            | Exp::Integer(_)
+            | Exp::BigInteger(_)
+            | Exp::Rational(_, _)
+            | Exp::BigRational(_, _)
+            | Exp::Complex(_, _) => number_to_string(exp),
+            Exp::Symbol(x) => x.to_string(),
+            Exp::SchemeString(x) if write => {
+                let mut s = String::from("\"");
+                for c in x.chars() {
+                    match c {
+                        '"' => s.push_str("\\\""),
+                        '\\' => s.push_str("\\\\"),
+                        '\n' => s.push_str("\\n"),
+                        '\t' => s.push_str("\\t"),
+                        _ => s.push(c),
+                    }
+                }
+                s.push('"');
+                s
+            }
+            Exp::SchemeString(x) => x.clone(),
+            Exp::Bool(x) => {
+                if *x {
+                    "#t".to_string()
+                } else {
+                    "#f".to_string()
+                }
+            }
+            Exp::Character(x) if write => match x {
+                ' ' => "#\\space".to_string(),
+                '\n' => "#\\newline".to_string(),
+                '\t' => "#\\tab".to_string(),
+                _ => format!("#\\{}", x),
+            },
+            Exp::Character(x) => x.to_string(),
+            Exp::Primitive(x) => format!("#<primitive {}>", x),
+            Exp::Procedure(_) => "#<procedure>".to_string(),
+            Exp::Record(x) => record_to_string(x, write),
+            Exp::RecordType(x) => format!("#<record-type {}>", x.display_name()),
+            Exp::RecordProcedure(_) => "#<record-procedure>".to_string(),
+            Exp::Parameter(_) => "#<parameter>".to_string(),
+            Exp::Port(_) => "#<port>".to_string(),
+            Exp::Eof => "#<eof>".to_string(),
+            Exp::Unspecified => "#!unspecific".to_string(),
+            Exp::Environment(_) => "#<environment>".to_string(),
+            Exp::Bytevector(x) => {
+                let bytes: Vec<String> = x.borrow().iter().map(|x| x.to_string()).collect();
+                format!("#u8({})", bytes.join(" "))
             }
-            Exp::SchemeString(x) => print!("{}", x),
-            Exp::Bool(x) => print!("{}", x),
-            Exp::List(Pair::Nil) => {
-                print!("()");
+            Exp::Vector(x) => {
+                let items: Vec<String> = x.iter().map(|x| exp_to_string(x, write)).collect();
+                format!("#({})", items.join(" "))
             }
+            Exp::List(Pair::Nil) => "()".to_string(),
             Exp::List(Pair::Cons(x, y)) => {
-                print!("(");
-                print(*x);
-                if *y != Pair::Nil {
-                    print!(" ");
-                }
-                let mut temp = y;
-                while let Pair::Cons(lhs, rhs) = *temp {
-                    print(*lhs);
-                    if *rhs == Pair::Nil {
-                        break;
-                    }
-                    print!(" ");
+                let mut s = String::from("(");
+                s.push_str(&exp_to_string(x, write));
+                let mut temp = y.clone();
+                while let Pair::Cons(lhs, rhs) = (*temp).clone() {
+                    s.push(' ');
+                    s.push_str(&exp_to_string(&lhs, write));
                     temp = rhs;
                 }
-                print!(")");
+                s.push(')');
+                s
             }
         }
     }
 
+    #[allow(dead_code)]
+    pub fn print(exp: Exp) {
+        print!("{}", exp_to_string(&exp, false));
+    }
+
     #[allow(dead_code)]
     pub fn pretty_print(exp: Exp) {
         print!("value: ");
         print(exp);
-        print!("\n");
+        println!();
     }
 
     #[allow(dead_code)]
     pub fn test_display() {
-        let plus = Exp::Symbol("+".to_string());
-        let x = Exp::Symbol("x".to_string());
+        let plus = Exp::Symbol(Symbol::new("+"));
+        let x = Exp::Symbol(Symbol::new("x"));
         let null = Exp::List(Pair::Nil);
         //  parameters: (x)
         let parameters = scheme_cons(x.clone(), null.clone());
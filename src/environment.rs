@@ -1,14 +1,36 @@
 pub mod env {
-    use crate::core_of_interpreter::core_of_interpreter::{Env, Exp, Pair};
+    use crate::core_of_interpreter::core_of_interpreter::{Env, Exp, Pair, ENV};
     use crate::tool::tools::{scheme_cons, set_car, set_cdr};
     use crate::{
         represent::represent::*,
         scheme_list,
         tool::tools::{append, list_length},
     };
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     const THE_EMPTY_ENVIRONMENT: Exp = Exp::List(Pair::Nil);
 
+    /// An environment as a Scheme value. The global one is looked up when
+    /// it is used, so it sees the definitions made after it was taken. A
+    /// captured one keeps its own frames, which eval may define into.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Environment {
+        Global,
+        Captured(Rc<RefCell<Env>>),
+    }
+
+    #[allow(dead_code)]
+    impl Environment {
+        #[allow(static_mut_refs)]
+        pub fn env(&self) -> Env {
+            match self {
+                Environment::Global => unsafe { ENV.clone() },
+                Environment::Captured(x) => Env::clone(&x.borrow()),
+            }
+        }
+    }
+
     // frame operatons
     #[allow(dead_code)]
     pub fn make_frame(variables: Exp, values: Exp) -> Exp {
@@ -184,6 +206,7 @@ mod test {
     };
     use crate::core_of_interpreter::core_of_interpreter::{Env, Exp, Pair};
     use crate::scheme_list;
+    use crate::symbol::symbol::Symbol;
     use crate::tool::tools::{append, generate_test_frames, scheme_cons};
 
     #[test]
@@ -206,7 +229,7 @@ mod test {
 
     #[test]
     fn test_add_binding_to_frame() {
-        let a = Exp::Symbol("a".to_string());
+        let a = Exp::Symbol(Symbol::new("a"));
         let four = Exp::Integer(4);
         let data = generate_test_frames();
         let s = add_binding_to_frame(a, four, data.frame);
@@ -216,63 +239,63 @@ mod test {
     #[test]
     fn test_lookup_variable_value() {
         let frame_one = generate_test_frames().frame;
-        let u = Exp::Symbol("u".to_string());
-        let v = Exp::Symbol("v".to_string());
+        let u = Exp::Symbol(Symbol::new("u"));
+        let v = Exp::Symbol(Symbol::new("v"));
         let four = Exp::Integer(4);
         let five = Exp::Integer(5);
         let frame_two = scheme_list!(scheme_list!(u, v), four, five);
         let test_env = Env(scheme_list!(frame_one, frame_two));
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("u".to_string()), test_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("u")), test_env.clone()),
             Exp::Integer(4)
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("v".to_string()), test_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("v")), test_env.clone()),
             Exp::Integer(5)
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("z".to_string()), test_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("z")), test_env.clone()),
             Exp::Integer(3)
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("x".to_string()), test_env),
+            lookup_variable_value(Exp::Symbol(Symbol::new("x")), test_env),
             Exp::Integer(1)
         );
     }
     #[test]
     fn test_set_variable_value() {
         let frame_one = generate_test_frames().frame;
-        let u = Exp::Symbol("u".to_string());
-        let v = Exp::Symbol("v".to_string());
+        let u = Exp::Symbol(Symbol::new("u"));
+        let v = Exp::Symbol(Symbol::new("v"));
         let four = Exp::Integer(4);
         let five = Exp::Integer(5);
         let frame_two = scheme_list!(scheme_list!(u, v), four, five);
         let test_env = Env(scheme_list!(frame_one, frame_two));
         let modified_env = set_variable_value(
-            Exp::Symbol("v".to_string()),
+            Exp::Symbol(Symbol::new("v")),
             Exp::Integer(0),
             test_env.clone(),
         );
         let another_env = set_variable_value(
-            Exp::Symbol("u".to_string()),
+            Exp::Symbol(Symbol::new("u")),
             Exp::Integer(9),
             modified_env.clone(),
         );
         let one_more_env = set_variable_value(
-            Exp::Symbol("z".to_string()),
+            Exp::Symbol(Symbol::new("z")),
             Exp::Integer(1000),
             another_env.clone(),
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("v".to_string()), modified_env),
+            lookup_variable_value(Exp::Symbol(Symbol::new("v")), modified_env),
             Exp::Integer(0)
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("u".to_string()), another_env),
+            lookup_variable_value(Exp::Symbol(Symbol::new("u")), another_env),
             Exp::Integer(9)
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("z".to_string()), one_more_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("z")), one_more_env.clone()),
             Exp::Integer(1000)
         );
     }
@@ -280,52 +303,52 @@ mod test {
     #[test]
     fn test_define_variable() {
         let frame_one = generate_test_frames().frame;
-        let u = Exp::Symbol("u".to_string());
-        let v = Exp::Symbol("v".to_string());
+        let u = Exp::Symbol(Symbol::new("u"));
+        let v = Exp::Symbol(Symbol::new("v"));
         let four = Exp::Integer(4);
         let five = Exp::Integer(5);
         let frame_two = scheme_list!(scheme_list!(u, v), four, five);
         let mut test_env = Env(scheme_list!(frame_one, frame_two));
-        test_env = define_variable(Exp::Symbol("x".to_string()), Exp::Integer(0), test_env);
+        test_env = define_variable(Exp::Symbol(Symbol::new("x")), Exp::Integer(0), test_env);
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("x".to_string()), test_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("x")), test_env.clone()),
             Exp::Integer(0)
         );
-        test_env = define_variable(Exp::Symbol("s".to_string()), Exp::Integer(101), test_env);
+        test_env = define_variable(Exp::Symbol(Symbol::new("s")), Exp::Integer(101), test_env);
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("s".to_string()), test_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("s")), test_env.clone()),
             Exp::Integer(101)
         );
-        test_env = define_variable(Exp::Symbol("y".to_string()), Exp::Integer(333), test_env);
+        test_env = define_variable(Exp::Symbol(Symbol::new("y")), Exp::Integer(333), test_env);
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("y".to_string()), test_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("y")), test_env.clone()),
             Exp::Integer(333)
         );
         let mut another_env = define_variable(
-            Exp::Symbol("p".to_string()),
+            Exp::Symbol(Symbol::new("p")),
             Exp::Integer(100),
             Env(Exp::List(Pair::Nil)),
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("p".to_string()), another_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("p")), another_env.clone()),
             Exp::Integer(100)
         );
         another_env = define_variable(
-            Exp::Symbol("d".to_string()),
+            Exp::Symbol(Symbol::new("d")),
             Exp::Integer(27),
             another_env.clone(),
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("d".to_string()), another_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("d")), another_env.clone()),
             Exp::Integer(27)
         );
         another_env = define_variable(
-            Exp::Symbol("g".to_string()),
+            Exp::Symbol(Symbol::new("g")),
             Exp::Integer(81),
             another_env.clone(),
         );
         assert_eq!(
-            lookup_variable_value(Exp::Symbol("g".to_string()), another_env.clone()),
+            lookup_variable_value(Exp::Symbol(Symbol::new("g")), another_env.clone()),
             Exp::Integer(81)
         );
     }
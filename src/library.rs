@@ -0,0 +1,489 @@
+#[allow(clippy::module_inception)]
+pub mod library {
+    use crate::core_of_interpreter::core_of_interpreter::{
+        eval, load_file, resolve_file_name, setup_environment, Env, Exp, Pair, ENV,
+    };
+    use crate::display::display::exp_to_string;
+    use crate::environment::env::{define_variable, first_frame, lookup_variable_value};
+    use crate::parameter::parameter::Parameter;
+    use crate::parser::parser::read_forms_from_file;
+    use crate::represent::represent::{cadr, car, cddr, cdr};
+    use crate::symbol::symbol::{Symbol, BEGIN, EXPORT, IMPORT, INCLUDE};
+    use crate::tool::tools::{append, list_to_vec, vec_to_list};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    /// A library made by define-library. It has its own global
+    /// environment, and only its exports are seen by an importer.
+    #[derive(Debug)]
+    pub struct Library {
+        pub env: Env,
+        // (external name, internal name)
+        pub exports: Vec<(Symbol, Symbol)>,
+    }
+
+    // the primitives of the libraries named by R7RS, (scheme base) has
+    // every primitive that is not in one of the others
+    const BUILTIN_LIBRARIES: [(&str, &[&str]); 12] = [
+        ("(scheme write)", &["display", "write"]),
+        (
+            "(scheme file)",
+            &[
+                "open-input-file",
+                "open-output-file",
+                "call-with-input-file",
+                "call-with-output-file",
+                "with-output-to-file",
+            ],
+        ),
+        ("(scheme load)", &["load"]),
+        ("(scheme read)", &["read"]),
+        ("(scheme eval)", &["eval"]),
+        (
+            "(scheme process-context)",
+            &[
+                "command-line",
+                "exit",
+                "emergency-exit",
+                "get-environment-variable",
+                "get-environment-variables",
+            ],
+        ),
+        (
+            "(scheme time)",
+            &["current-second", "current-jiffy", "jiffies-per-second"],
+        ),
+        (
+            "(scheme repl)",
+            &["interaction-environment", "system-global-environment"],
+        ),
+        (
+            "(scheme inexact)",
+            &[
+                "sqrt", "exp", "log", "sin", "cos", "tan", "asin", "acos", "atan",
+            ],
+        ),
+        (
+            "(scheme complex)",
+            &[
+                "make-rectangular",
+                "make-polar",
+                "real-part",
+                "imag-part",
+                "magnitude",
+                "angle",
+            ],
+        ),
+        (
+            "(srfi 1)",
+            &[
+                "filter",
+                "remove",
+                "reduce",
+                "delete",
+                "iota",
+                "any",
+                "every",
+                "last-pair",
+            ],
+        ),
+        (
+            "(srfi 151)",
+            &[
+                "bitwise-and",
+                "bitwise-or",
+                "bitwise-xor",
+                "bitwise-not",
+                "arithmetic-shift",
+                "bit-count",
+                "integer-length",
+                "bit-set?",
+            ],
+        ),
+    ];
+
+    thread_local! {
+        static LIBRARIES: RefCell<HashMap<Symbol, Library>> = RefCell::new(HashMap::new());
+        // the library whose global environment is in use, None for the
+        // program itself
+        static CURRENT_LIBRARY: RefCell<Option<Symbol>> = const { RefCell::new(None) };
+        // the environment given to eval, it stands in for the global
+        // environment while eval runs
+        static CAPTURED_ENVIRONMENT: RefCell<Option<Rc<RefCell<Env>>>> =
+            const { RefCell::new(None) };
+        static LIBRARY_SEARCH_PATH: Rc<Parameter> = Rc::new(Parameter {
+            value: RefCell::new(default_search_path()),
+            converter: None,
+        });
+        static BUILTIN_LIBRARY_TABLE: HashMap<&'static str, Vec<(Symbol, Exp)>> =
+            builtin_library_table();
+    }
+
+    // the current directory, then the directories of RUSCHEME_LIBRARY_PATH
+    fn default_search_path() -> Exp {
+        let mut dirs = vec![Exp::SchemeString(".".to_string())];
+        if let Ok(x) = std::env::var("RUSCHEME_LIBRARY_PATH") {
+            for dir in std::env::split_paths(&x) {
+                dirs.push(Exp::SchemeString(dir.to_string_lossy().to_string()));
+            }
+        }
+        vec_to_list(&dirs)
+    }
+
+    /// The parameter behind library-search-path, a list of the
+    /// directories where (import (foo bar)) looks for foo/bar.sld.
+    #[allow(dead_code)]
+    pub fn library_search_path() -> Rc<Parameter> {
+        LIBRARY_SEARCH_PATH.with(|x| x.clone())
+    }
+
+    #[allow(dead_code)]
+    pub fn current_library() -> Option<Symbol> {
+        CURRENT_LIBRARY.with(|x| *x.borrow())
+    }
+
+    #[allow(dead_code)]
+    pub fn in_captured_environment() -> bool {
+        CAPTURED_ENVIRONMENT.with(|x| x.borrow().is_some())
+    }
+
+    /// The global environment of the current library, or of the program,
+    /// or the captured environment eval is running in.
+    #[allow(dead_code, static_mut_refs)]
+    pub fn global_environment() -> Env {
+        if let Some(env) = CAPTURED_ENVIRONMENT.with(|x| x.borrow().clone()) {
+            return Env::clone(&env.borrow());
+        }
+        match current_library() {
+            Some(name) => LIBRARIES.with(|x| x.borrow()[&name].env.clone()),
+            None => unsafe { ENV.clone() },
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn set_global_environment(env: Env) {
+        if let Some(captured) = CAPTURED_ENVIRONMENT.with(|x| x.borrow().clone()) {
+            captured.replace(env);
+            return;
+        }
+        match current_library() {
+            Some(name) => LIBRARIES.with(|x| x.borrow_mut().get_mut(&name).unwrap().env = env),
+            None => unsafe { ENV = env },
+        }
+    }
+
+    /// Makes a library current while one of its procedures runs, or a
+    /// captured environment while eval runs in it. The previous ones come
+    /// back when the guard is dropped.
+    #[allow(dead_code)]
+    pub struct LibraryGuard {
+        saved: Option<Symbol>,
+        saved_environment: Option<Rc<RefCell<Env>>>,
+    }
+
+    #[allow(dead_code)]
+    impl LibraryGuard {
+        pub fn new(library: Option<Symbol>) -> Self {
+            let saved = CURRENT_LIBRARY.with(|x| x.replace(library));
+            let saved_environment = CAPTURED_ENVIRONMENT.with(|x| x.replace(None));
+            LibraryGuard {
+                saved,
+                saved_environment,
+            }
+        }
+
+        /// Definitions and assignments go to env instead of the global
+        /// environment, which is left as it was.
+        pub fn captured(env: Rc<RefCell<Env>>) -> Self {
+            let saved = current_library();
+            let saved_environment = CAPTURED_ENVIRONMENT.with(|x| x.replace(Some(env)));
+            LibraryGuard {
+                saved,
+                saved_environment,
+            }
+        }
+    }
+
+    impl Drop for LibraryGuard {
+        fn drop(&mut self) {
+            CURRENT_LIBRARY.with(|x| x.replace(self.saved));
+            CAPTURED_ENVIRONMENT.with(|x| x.replace(self.saved_environment.take()));
+        }
+    }
+
+    // (foo bar) is known as the symbol "(foo bar)"
+    fn library_key(name: &Exp) -> Symbol {
+        Symbol::new(&exp_to_string(name, false))
+    }
+
+    fn symbol_of(x: Exp) -> Symbol {
+        match x {
+            Exp::Symbol(x) => x,
+            _ => panic!("not an identifier: {}", exp_to_string(&x, false)),
+        }
+    }
+
+    /// (define-library name declaration ...)
+    #[allow(dead_code)]
+    pub fn eval_library_definition(exp: Exp) -> Result<Exp, &'static str> {
+        let key = library_key(&cadr(exp.clone()).unwrap());
+        let declarations = list_to_vec(cddr(exp).unwrap());
+        let mut env = Env(Exp::List(Pair::Nil));
+        let mut export_specs = vec![];
+        let mut body = Exp::List(Pair::Nil);
+        for declaration in declarations {
+            let rest = cdr(declaration.clone()).unwrap();
+            match car(declaration).unwrap() {
+                Exp::Symbol(EXPORT) => export_specs.extend(list_to_vec(rest)),
+                Exp::Symbol(IMPORT) => {
+                    for (name, value) in import_bindings(rest) {
+                        env = define_variable(Exp::Symbol(name), value, env);
+                    }
+                }
+                Exp::Symbol(BEGIN) => body = append(body, rest),
+                Exp::Symbol(INCLUDE) => {
+                    for file in list_to_vec(rest) {
+                        match file {
+                            Exp::SchemeString(x) => {
+                                let path = resolve_file_name(&x);
+                                body = append(body, read_forms_from_file(&path.to_string_lossy()))
+                            }
+                            _ => panic!("include needs file names!"),
+                        }
+                    }
+                }
+                _ => panic!("unknown library declaration!"),
+            }
+        }
+        LIBRARIES.with(|x| {
+            x.borrow_mut().insert(
+                key,
+                Library {
+                    env,
+                    exports: vec![],
+                },
+            )
+        });
+        {
+            let _guard = LibraryGuard::new(Some(key));
+            for form in list_to_vec(body) {
+                eval(form, global_environment())?;
+            }
+        }
+        // (rename internal external) or a name exported as itself
+        let exports = export_specs
+            .into_iter()
+            .map(|spec| match spec {
+                Exp::Symbol(x) => (x, x),
+                _ => (
+                    symbol_of(car(cddr(spec.clone()).unwrap()).unwrap()),
+                    symbol_of(cadr(spec).unwrap()),
+                ),
+            })
+            .collect();
+        LIBRARIES.with(|x| x.borrow_mut().get_mut(&key).unwrap().exports = exports);
+        Ok(Exp::Unspecified)
+    }
+
+    /// (import import-set ...) binds the imported names in the current
+    /// global environment.
+    #[allow(dead_code)]
+    pub fn eval_import(exp: Exp) -> Exp {
+        let mut env = global_environment();
+        for (name, value) in import_bindings(cdr(exp).unwrap()) {
+            env = define_variable(Exp::Symbol(name), value, env);
+        }
+        set_global_environment(env);
+        Exp::Unspecified
+    }
+
+    fn import_bindings(import_sets: Exp) -> Vec<(Symbol, Exp)> {
+        let mut bindings = vec![];
+        for set in list_to_vec(import_sets) {
+            bindings.extend(import_set_bindings(set));
+        }
+        bindings
+    }
+
+    // only, except, prefix and rename filter the bindings of the import
+    // set they wrap, anything else names a library
+    fn import_set_bindings(set: Exp) -> Vec<(Symbol, Exp)> {
+        let tag = match car(set.clone()).unwrap() {
+            Exp::Symbol(x) => x.as_str(),
+            _ => "",
+        };
+        let is_modifier = cdr(set.clone()).unwrap().is_pair()
+            && car(cdr(set.clone()).unwrap()).unwrap().is_pair();
+        if !is_modifier || !["only", "except", "prefix", "rename"].contains(&tag) {
+            return library_bindings(&set);
+        }
+        let bindings = import_set_bindings(cadr(set.clone()).unwrap());
+        let args = list_to_vec(cddr(set).unwrap());
+        match tag {
+            "only" => {
+                let names: Vec<Symbol> = args.into_iter().map(symbol_of).collect();
+                check_imported(&names, &bindings);
+                bindings
+                    .into_iter()
+                    .filter(|(name, _)| names.contains(name))
+                    .collect()
+            }
+            "except" => {
+                let names: Vec<Symbol> = args.into_iter().map(symbol_of).collect();
+                check_imported(&names, &bindings);
+                bindings
+                    .into_iter()
+                    .filter(|(name, _)| !names.contains(name))
+                    .collect()
+            }
+            "prefix" => {
+                let prefix = symbol_of(args[0].clone());
+                bindings
+                    .into_iter()
+                    .map(|(name, value)| (Symbol::new(&format!("{}{}", prefix, name)), value))
+                    .collect()
+            }
+            _ => {
+                let renames: Vec<(Symbol, Symbol)> = args
+                    .into_iter()
+                    .map(|x| {
+                        (
+                            symbol_of(car(x.clone()).unwrap()),
+                            symbol_of(cadr(x).unwrap()),
+                        )
+                    })
+                    .collect();
+                let names: Vec<Symbol> = renames.iter().map(|x| x.0).collect();
+                check_imported(&names, &bindings);
+                bindings
+                    .into_iter()
+                    .map(|(name, value)| match renames.iter().find(|x| x.0 == name) {
+                        Some((_, new_name)) => (*new_name, value),
+                        None => (name, value),
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // only, except and rename can only name what the import set has
+    fn check_imported(names: &[Symbol], bindings: &[(Symbol, Exp)]) {
+        for name in names {
+            if !bindings.iter().any(|(x, _)| x == name) {
+                panic!("not in the import set: {}", name);
+            }
+        }
+    }
+
+    // the exports of a library, which is loaded from the search path the
+    // first time it is imported
+    fn library_bindings(name: &Exp) -> Vec<(Symbol, Exp)> {
+        let key = library_key(name);
+        if let Some(bindings) = builtin_library_bindings(key.as_str()) {
+            return bindings;
+        }
+        if !LIBRARIES.with(|x| x.borrow().contains_key(&key)) {
+            load_library(name);
+        }
+        LIBRARIES.with(|x| {
+            let libraries = x.borrow();
+            let library = match libraries.get(&key) {
+                Some(x) => x,
+                None => panic!("unknown library: {}", key),
+            };
+            library
+                .exports
+                .iter()
+                .map(|(external, internal)| {
+                    (
+                        *external,
+                        lookup_variable_value(Exp::Symbol(*internal), library.env.clone()),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn builtin_library_bindings(key: &str) -> Option<Vec<(Symbol, Exp)>> {
+        BUILTIN_LIBRARY_TABLE.with(|x| x.get(key).cloned())
+    }
+
+    // the bindings of every built-in library, taken once from the first
+    // frame of a fresh global environment
+    fn builtin_library_table() -> HashMap<&'static str, Vec<(Symbol, Exp)>> {
+        let frame = first_frame(setup_environment());
+        let bindings: Vec<(Symbol, Exp)> = list_to_vec(car(frame.clone()).unwrap())
+            .into_iter()
+            .map(symbol_of)
+            .zip(list_to_vec(cdr(frame).unwrap()))
+            .collect();
+        let mut table = HashMap::new();
+        let base = bindings
+            .iter()
+            .filter(|(name, _)| {
+                BUILTIN_LIBRARIES
+                    .iter()
+                    .all(|(_, names)| !names.contains(&name.as_str()))
+            })
+            .cloned()
+            .collect();
+        table.insert("(scheme base)", base);
+        for (key, names) in BUILTIN_LIBRARIES.iter() {
+            let library = bindings
+                .iter()
+                .filter(|(name, _)| names.contains(&name.as_str()))
+                .cloned()
+                .collect();
+            table.insert(*key, library);
+        }
+        table
+    }
+
+    // (foo bar) is looked for as foo/bar.sld, then foo/bar.scm, in each
+    // directory of the search path
+    fn load_library(name: &Exp) {
+        let parts: Vec<String> = list_to_vec(name.clone())
+            .iter()
+            .map(|x| exp_to_string(x, false))
+            .collect();
+        let dirs = list_to_vec(library_search_path().value.borrow().clone());
+        for dir in dirs {
+            let dir = match dir {
+                Exp::SchemeString(x) => x,
+                _ => panic!("library search path must be a list of strings!"),
+            };
+            for extension in ["sld", "scm"] {
+                let path = Path::new(&dir)
+                    .join(parts.join("/"))
+                    .with_extension(extension);
+                if path.is_file() {
+                    let _guard = LibraryGuard::new(None);
+                    load_file(&path);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::library::{current_library, LibraryGuard};
+    use crate::symbol::symbol::Symbol;
+
+    #[test]
+    fn test_library_guard() {
+        assert_eq!(current_library(), None);
+        {
+            let _guard = LibraryGuard::new(Some(Symbol::new("(a)")));
+            {
+                let _guard = LibraryGuard::new(None);
+                assert_eq!(current_library(), None);
+            }
+            assert_eq!(current_library(), Some(Symbol::new("(a)")));
+        }
+        assert_eq!(current_library(), None);
+    }
+}
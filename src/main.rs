@@ -2,15 +2,28 @@
 //!
 //! ruscheme is a Scheme interpreter implemented in Rust which runs
 //! rather fast.
+mod bigint;
+mod clock;
 mod core_of_interpreter;
 mod display;
 mod environment;
+mod library;
+mod number;
+mod parameter;
 mod parser;
+mod port;
+mod process;
+mod random;
+mod record;
 mod represent;
+mod symbol;
 mod tool;
 use crate::parser::parser::*;
-use core_of_interpreter::core_of_interpreter::{eval, Exp, ENV};
+use core_of_interpreter::core_of_interpreter::{eval, load, setup_environment, Exp, ENV};
 use display::display::pretty_print;
+use port::port::flush_output_ports;
+use process::process::{install_exit_hook, run_until_exit, set_command_line};
+use std::env;
 use std::io::{self, Write};
 
 fn prompt_for_input(s: String) {
@@ -18,33 +31,69 @@ fn prompt_for_input(s: String) {
     io::stdout().flush().unwrap();
 }
 
-fn input() -> Exp {
+// None once stdin has ended
+fn input() -> Option<Exp> {
     let mut programs: Vec<String> = vec![];
-    let _input = read_scheme_programs_from_stdin(&mut programs);
-    let mut tokens = tokenize(&mut programs);
-    let x = build_syntax_tree(&mut tokens);
-    x
+    match read_scheme_programs_from_stdin(&mut programs) {
+        Ok(true) => {
+            let mut tokens = tokenize(&programs);
+            Some(build_syntax_tree(&mut tokens))
+        }
+        _ => None,
+    }
 }
+
+#[allow(static_mut_refs)]
 fn driver_loop() {
-    let input_prompt = String::from("|-> ");
-    prompt_for_input(input_prompt);
-    let exp = input();
-    unsafe {
-        let output = eval(exp, ENV.clone()).unwrap();
-        match output {
-            Some(x) => {
-                print!("=> ");
-                pretty_print(x);
+    loop {
+        let input_prompt = String::from("|-> ");
+        prompt_for_input(input_prompt);
+        let exp = match input() {
+            Some(exp) => exp,
+            None => {
+                println!();
+                return;
+            }
+        };
+        unsafe {
+            // a definition or display has nothing worth printing, an
+            // error is reported and the loop goes on
+            match eval(exp, ENV.clone()) {
+                Ok(Exp::Unspecified) => println!("=> value: OK"),
+                Ok(x) => {
+                    print!("=> ");
+                    pretty_print(x);
+                }
+                Err(e) => println!("=> error: {}", e),
             }
-            None => println!("=> value: OK"),
         }
     }
-    driver_loop();
 }
 
+/// With a file argument, the file is run as a script and (command-line)
+/// starts with its name, otherwise the REPL is started. The process ends
+/// with the code given to exit, 0 if it is not called.
 fn main() {
-    println!("This is an interpreter for a subset of Scheme language implemented in Rust.");
-    println!("Author: Yi; Image saved on Monday February 8, 2021 at 7:35 PM");
-    println!("Happy New Year ^_^");
-    driver_loop();
+    let args: Vec<String> = env::args().collect();
+    let script = args.get(1).cloned();
+    match script {
+        Some(_) => set_command_line(args[1..].to_vec()),
+        None => set_command_line(args),
+    }
+    install_exit_hook();
+    unsafe {
+        ENV = setup_environment();
+    }
+    let code = run_until_exit(|| match script {
+        Some(name) => load(&name),
+        None => {
+            println!("This is an interpreter for a subset of Scheme language implemented in Rust.");
+            println!("Author: Yi; Image saved on Monday February 8, 2021 at 7:35 PM");
+            println!("Happy New Year ^_^");
+            driver_loop();
+        }
+    });
+    flush_output_ports();
+    io::stdout().flush().unwrap();
+    std::process::exit(code);
 }
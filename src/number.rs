@@ -0,0 +1,1457 @@
+#[allow(clippy::module_inception)]
+pub mod number {
+    use crate::bigint::bigint::BigInt;
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use std::cmp::Ordering;
+    use std::convert::TryFrom;
+    use std::rc::Rc;
+
+    /* exact numbers are kept as (numerator, denominator) with a positive
+     * denominator and no common factor; an integer is just n/1 */
+    #[allow(dead_code)]
+    pub fn gcd(a: i64, b: i64) -> i64 {
+        gcd_i128(a as i128, b as i128) as i64
+    }
+
+    fn gcd_i128(a: i128, b: i128) -> i128 {
+        let mut x = a.abs();
+        let mut y = b.abs();
+        while y != 0 {
+            let t = x % y;
+            x = y;
+            y = t;
+        }
+        x
+    }
+
+    fn normalize(numer: i128, denom: i128) -> Exp {
+        if denom == 0 {
+            panic!("divide by zero!");
+        }
+        let g = gcd_i128(numer, denom);
+        let mut n = numer / g;
+        let mut d = denom / g;
+        if d < 0 {
+            n = -n;
+            d = -d;
+        }
+        if d == 1 {
+            from_big_integer(BigInt::from_i128(n))
+        } else {
+            match (i64::try_from(n), i64::try_from(d)) {
+                (Ok(n), Ok(d)) => Exp::Rational(n, d),
+                _ => Exp::BigRational(Rc::new(BigInt::from_i128(n)), Rc::new(BigInt::from_i128(d))),
+            }
+        }
+    }
+
+    // normalize for parts of any size, the parts of the result are only
+    // big when one of them does not fit in an i64
+    fn normalize_big(numer: BigInt, denom: BigInt) -> Exp {
+        let zero = BigInt::from_i128(0);
+        if denom == zero {
+            panic!("divide by zero!");
+        }
+        let g = big_gcd(&numer, &denom);
+        let mut n = numer.divide(&g).0;
+        let mut d = denom.divide(&g).0;
+        if d.is_negative() {
+            n = n.negate();
+            d = d.negate();
+        }
+        if d == BigInt::from_i128(1) {
+            return from_big_integer(n);
+        }
+        match (n.to_i64(), d.to_i64()) {
+            (Some(n), Some(d)) => Exp::Rational(n, d),
+            _ => Exp::BigRational(Rc::new(n), Rc::new(d)),
+        }
+    }
+
+    /// An exact integer, which is an Exp::Integer whenever it fits in
+    /// an i64 and an Exp::BigInteger otherwise.
+    #[allow(dead_code)]
+    pub fn from_big_integer(n: BigInt) -> Exp {
+        match n.to_i64() {
+            Some(x) => Exp::Integer(x),
+            None => Exp::BigInteger(Rc::new(n)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn to_big_integer(x: &Exp) -> Option<BigInt> {
+        match x {
+            Exp::Integer(n) => Some(BigInt::from_i128(*n as i128)),
+            Exp::BigInteger(n) => Some(BigInt::clone(n)),
+            _ => None,
+        }
+    }
+
+    // both operands as big integers, when they are exact integers and
+    // one of them does not fit in an i64
+    fn big_operands(lhs: &Exp, rhs: &Exp) -> Option<(BigInt, BigInt)> {
+        match (lhs, rhs) {
+            (Exp::BigInteger(_), _) | (_, Exp::BigInteger(_)) => {
+                Some((to_big_integer(lhs)?, to_big_integer(rhs)?))
+            }
+            _ => None,
+        }
+    }
+
+    // both operands as big numerators and denominators, when they are
+    // exact and one of them has big parts, so a big integer mixed with a
+    // rational stays exact
+    #[allow(clippy::type_complexity)]
+    fn big_rational_operands(lhs: &Exp, rhs: &Exp) -> Option<((BigInt, BigInt), (BigInt, BigInt))> {
+        match (lhs, rhs) {
+            (Exp::BigRational(_, _), _)
+            | (_, Exp::BigRational(_, _))
+            | (Exp::BigInteger(_), _)
+            | (_, Exp::BigInteger(_)) => Some((big_exact_parts(lhs)?, big_exact_parts(rhs)?)),
+            _ => None,
+        }
+    }
+
+    /// Build the exact number numer/denom in lowest terms, an integer
+    /// is returned whenever the denominator divides out.
+    /// # Example
+    /// ```
+    /// assert_eq!(make_rational(6, 4), Exp::Rational(3, 2));
+    /// assert_eq!(make_rational(6, 3), Exp::Integer(2));
+    /// ```
+    #[allow(dead_code)]
+    pub fn make_rational(numer: i64, denom: i64) -> Exp {
+        normalize(numer as i128, denom as i128)
+    }
+
+    /// make_rational for a numerator and a denominator of any size.
+    #[allow(dead_code)]
+    pub fn make_big_rational(numer: BigInt, denom: BigInt) -> Exp {
+        normalize_big(numer, denom)
+    }
+
+    #[allow(dead_code, clippy::match_like_matches_macro)]
+    pub fn is_exact(x: &Exp) -> bool {
+        match x {
+            Exp::Integer(_x) => true,
+            Exp::BigInteger(_x) => true,
+            Exp::Rational(_x, _y) => true,
+            Exp::BigRational(_x, _y) => true,
+            _ => false,
+        }
+    }
+
+    fn exact_parts(x: &Exp) -> Option<(i128, i128)> {
+        match x {
+            Exp::Integer(n) => Some((*n as i128, 1)),
+            Exp::Rational(n, d) => Some((*n as i128, *d as i128)),
+            _ => None,
+        }
+    }
+
+    fn big_exact_parts(x: &Exp) -> Option<(BigInt, BigInt)> {
+        match x {
+            Exp::Integer(n) => Some((BigInt::from_i128(*n as i128), BigInt::from_i128(1))),
+            Exp::BigInteger(n) => Some((BigInt::clone(n), BigInt::from_i128(1))),
+            Exp::Rational(n, d) => {
+                Some((BigInt::from_i128(*n as i128), BigInt::from_i128(*d as i128)))
+            }
+            Exp::BigRational(n, d) => Some((BigInt::clone(n), BigInt::clone(d))),
+            _ => None,
+        }
+    }
+
+    fn checked(x: Option<i128>) -> i128 {
+        match x {
+            Some(x) => x,
+            None => panic!("integer overflow!"),
+        }
+    }
+
+    #[allow(clippy::match_like_matches_macro)]
+    fn is_complex(x: &Exp) -> bool {
+        match x {
+            Exp::Complex(_x, _y) => true,
+            _ => false,
+        }
+    }
+
+    fn is_exact_zero(x: &Exp) -> bool {
+        *x == Exp::Integer(0)
+    }
+
+    /* a complex number is a pair of real parts, the imaginary part
+     * is never an exact zero, such a number is just its real part */
+    #[allow(dead_code)]
+    pub fn make_rectangular(real: Exp, imag: Exp) -> Exp {
+        if is_complex(&real) || is_complex(&imag) || !real.is_number() || !imag.is_number() {
+            panic!("wrong type for make-rectangular!");
+        }
+        if is_exact_zero(&imag) {
+            real
+        } else {
+            Exp::Complex(Box::new(real), Box::new(imag))
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn make_polar(magnitude: Exp, angle: Exp) -> Exp {
+        if is_exact_zero(&angle) {
+            make_rectangular(magnitude, angle)
+        } else {
+            let m = to_float(&magnitude, "make-polar");
+            let a = to_float(&angle, "make-polar");
+            make_rectangular(Exp::FloatNumber(m * a.cos()), Exp::FloatNumber(m * a.sin()))
+        }
+    }
+
+    fn rectangular_parts(x: Exp) -> (Exp, Exp) {
+        match x {
+            Exp::Complex(real, imag) => (*real, *imag),
+            _ => (x, Exp::Integer(0)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn real_part(x: Exp) -> Exp {
+        if !x.is_number() {
+            panic!("wrong type for real-part!");
+        }
+        rectangular_parts(x).0
+    }
+
+    #[allow(dead_code)]
+    pub fn imag_part(x: Exp) -> Exp {
+        if !x.is_number() {
+            panic!("wrong type for imag-part!");
+        }
+        rectangular_parts(x).1
+    }
+
+    #[allow(dead_code)]
+    pub fn magnitude(x: Exp) -> Exp {
+        if !x.is_number() {
+            panic!("wrong type for magnitude!");
+        }
+        match x {
+            Exp::Complex(real, imag) => sqrt(add(
+                multiply(*real.clone(), *real),
+                multiply(*imag.clone(), *imag),
+            )),
+            _ => {
+                if greater_than(&Exp::Integer(0), &x) {
+                    negate(x)
+                } else {
+                    x
+                }
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn angle(x: Exp) -> Exp {
+        if !x.is_number() {
+            panic!("wrong type for angle!");
+        }
+        match x {
+            Exp::Complex(real, imag) => {
+                let re = to_float(&real, "angle");
+                let im = to_float(&imag, "angle");
+                Exp::FloatNumber(im.atan2(re))
+            }
+            _ => {
+                if greater_than(&Exp::Integer(0), &x) {
+                    Exp::FloatNumber(std::f64::consts::PI)
+                } else if is_exact(&x) {
+                    Exp::Integer(0)
+                } else {
+                    Exp::FloatNumber(0.0)
+                }
+            }
+        }
+    }
+
+    // n/d through a quotient of 64 significant bits, so that parts
+    // too large for a float still give a finite or subnormal result
+    fn big_ratio_to_f64(n: &BigInt, d: &BigInt) -> f64 {
+        let shift = d.integer_length() as i64 - n.integer_length() as i64 + 64;
+        let quotient = n.shift(shift).divide(d).0.to_f64();
+        // scaled in two steps as 2^-shift alone may not be a float
+        quotient * 2f64.powi(-(shift / 2) as i32) * 2f64.powi(-(shift - shift / 2) as i32)
+    }
+
+    fn to_float(x: &Exp, operation: &str) -> f64 {
+        match x {
+            Exp::Integer(n) => *n as f64,
+            Exp::BigInteger(n) => n.to_f64(),
+            Exp::Rational(n, d) => *n as f64 / *d as f64,
+            Exp::BigRational(n, d) => big_ratio_to_f64(n, d),
+            Exp::FloatNumber(x) => *x,
+            _ => panic!("wrong type for {}!", operation),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn add(lhs: Exp, rhs: Exp) -> Exp {
+        if is_complex(&lhs) || is_complex(&rhs) {
+            let (a, b) = rectangular_parts(lhs);
+            let (c, d) = rectangular_parts(rhs);
+            return make_rectangular(add(a, c), add(b, d));
+        }
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            return from_big_integer(a.add(&b));
+        }
+        if let Some(((a, b), (c, d))) = big_rational_operands(&lhs, &rhs) {
+            return normalize_big(a.multiply(&d).add(&c.multiply(&b)), b.multiply(&d));
+        }
+        match (exact_parts(&lhs), exact_parts(&rhs)) {
+            (Some((a, b)), Some((c, d))) => normalize(
+                checked(checked(a.checked_mul(d)).checked_add(checked(c.checked_mul(b)))),
+                b * d,
+            ),
+            _ => Exp::FloatNumber(to_float(&lhs, "add") + to_float(&rhs, "add")),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn substract(lhs: Exp, rhs: Exp) -> Exp {
+        if is_complex(&lhs) || is_complex(&rhs) {
+            let (a, b) = rectangular_parts(lhs);
+            let (c, d) = rectangular_parts(rhs);
+            return make_rectangular(substract(a, c), substract(b, d));
+        }
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            return from_big_integer(a.substract(&b));
+        }
+        if let Some(((a, b), (c, d))) = big_rational_operands(&lhs, &rhs) {
+            return normalize_big(a.multiply(&d).substract(&c.multiply(&b)), b.multiply(&d));
+        }
+        match (exact_parts(&lhs), exact_parts(&rhs)) {
+            (Some((a, b)), Some((c, d))) => normalize(
+                checked(checked(a.checked_mul(d)).checked_sub(checked(c.checked_mul(b)))),
+                b * d,
+            ),
+            _ => Exp::FloatNumber(to_float(&lhs, "substract") - to_float(&rhs, "substract")),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn multiply(lhs: Exp, rhs: Exp) -> Exp {
+        if is_complex(&lhs) || is_complex(&rhs) {
+            // (a+bi)(c+di) = (ac-bd) + (ad+bc)i
+            let (a, b) = rectangular_parts(lhs);
+            let (c, d) = rectangular_parts(rhs);
+            return make_rectangular(
+                substract(
+                    multiply(a.clone(), c.clone()),
+                    multiply(b.clone(), d.clone()),
+                ),
+                add(multiply(a, d), multiply(b, c)),
+            );
+        }
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            return from_big_integer(a.multiply(&b));
+        }
+        if let Some(((a, b), (c, d))) = big_rational_operands(&lhs, &rhs) {
+            return normalize_big(a.multiply(&c), b.multiply(&d));
+        }
+        match (exact_parts(&lhs), exact_parts(&rhs)) {
+            (Some((a, b)), Some((c, d))) => {
+                normalize(checked(a.checked_mul(c)), checked(b.checked_mul(d)))
+            }
+            _ => Exp::FloatNumber(to_float(&lhs, "multiply") * to_float(&rhs, "multiply")),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn divide(lhs: Exp, rhs: Exp) -> Exp {
+        if is_complex(&lhs) || is_complex(&rhs) {
+            // (a+bi)/(c+di) = ((ac+bd) + (bc-ad)i) / (c^2+d^2)
+            let (a, b) = rectangular_parts(lhs);
+            let (c, d) = rectangular_parts(rhs);
+            let denom = add(
+                multiply(c.clone(), c.clone()),
+                multiply(d.clone(), d.clone()),
+            );
+            return make_rectangular(
+                divide(
+                    add(
+                        multiply(a.clone(), c.clone()),
+                        multiply(b.clone(), d.clone()),
+                    ),
+                    denom.clone(),
+                ),
+                divide(substract(multiply(b, c), multiply(a, d)), denom),
+            );
+        }
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            // a quotient of big integers is an integer or a rational
+            if b == BigInt::from_i128(0) {
+                panic!("divide by zero!");
+            }
+            let (quotient, remainder) = a.divide(&b);
+            if remainder == BigInt::from_i128(0) {
+                return from_big_integer(quotient);
+            }
+            return normalize_big(a, b);
+        }
+        if let Some(((a, b), (c, d))) = big_rational_operands(&lhs, &rhs) {
+            return normalize_big(a.multiply(&d), b.multiply(&c));
+        }
+        match (exact_parts(&lhs), exact_parts(&rhs)) {
+            (Some((a, b)), Some((c, d))) => {
+                normalize(checked(a.checked_mul(d)), checked(b.checked_mul(c)))
+            }
+            _ => {
+                // only an exact zero is an error, inexact division follows IEEE 754
+                if is_exact_zero(&rhs) {
+                    panic!("divide by zero!");
+                }
+                Exp::FloatNumber(to_float(&lhs, "division") / to_float(&rhs, "division"))
+            }
+        }
+    }
+
+    /// Order two real numbers, None when a NaN makes them unordered.
+    #[allow(dead_code)]
+    pub fn compare(lhs: &Exp, rhs: &Exp) -> Option<Ordering> {
+        if let Some((a, b)) = big_operands(lhs, rhs) {
+            return Some(a.cmp(&b));
+        }
+        if let Some(((a, b), (c, d))) = big_rational_operands(lhs, rhs) {
+            return Some(a.multiply(&d).cmp(&c.multiply(&b)));
+        }
+        match (exact_parts(lhs), exact_parts(rhs)) {
+            // denominators are positive, so cross multiplication keeps the order
+            (Some((a, b)), Some((c, d))) => {
+                Some(checked(a.checked_mul(d)).cmp(&checked(c.checked_mul(b))))
+            }
+            _ => to_float(lhs, "comparision").partial_cmp(&to_float(rhs, "comparision")),
+        }
+    }
+
+    /// (= 1 1.0) holds, numbers are compared by value whatever their
+    /// exactness, complex numbers part by part.
+    #[allow(dead_code)]
+    pub fn is_numerically_equal(lhs: &Exp, rhs: &Exp) -> bool {
+        if is_complex(lhs) || is_complex(rhs) {
+            if !lhs.is_number() || !rhs.is_number() {
+                panic!("wrong type for comparision!");
+            }
+            let (a, b) = rectangular_parts(lhs.clone());
+            let (c, d) = rectangular_parts(rhs.clone());
+            is_numerically_equal(&a, &c) && is_numerically_equal(&b, &d)
+        } else {
+            compare(lhs, rhs) == Some(Ordering::Equal)
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn greater_than(lhs: &Exp, rhs: &Exp) -> bool {
+        compare(lhs, rhs) == Some(Ordering::Greater)
+    }
+
+    #[allow(dead_code)]
+    pub fn exact_to_inexact(x: Exp) -> Exp {
+        match x {
+            Exp::Complex(real, imag) => {
+                make_rectangular(exact_to_inexact(*real), exact_to_inexact(*imag))
+            }
+            _ => Exp::FloatNumber(to_float(&x, "exact->inexact")),
+        }
+    }
+
+    /// Every finite float is a dyadic rational, mantissa * 2^exponent,
+    /// so the conversion is always exact.
+    #[allow(dead_code)]
+    pub fn inexact_to_exact(x: Exp) -> Exp {
+        match x {
+            Exp::Integer(_) | Exp::BigInteger(_) => x,
+            Exp::Rational(_, _) | Exp::BigRational(_, _) => x,
+            Exp::FloatNumber(f) => float_to_exact(f),
+            Exp::Complex(real, imag) => {
+                make_rectangular(inexact_to_exact(*real), inexact_to_exact(*imag))
+            }
+            _ => panic!("wrong type for inexact->exact!"),
+        }
+    }
+
+    fn float_to_exact(f: f64) -> Exp {
+        if !f.is_finite() {
+            panic!("no exact representation!");
+        }
+        if f == 0.0 {
+            return Exp::Integer(0);
+        }
+        let bits = f.to_bits();
+        let sign: i128 = if bits >> 63 == 0 { 1 } else { -1 };
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & 0xf_ffff_ffff_ffff;
+        let (mut mantissa, mut exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 0x10_0000_0000_0000, biased_exponent - 1075)
+        };
+        while mantissa & 1 == 0 && exponent < 0 {
+            mantissa >>= 1;
+            exponent += 1;
+        }
+        let numer = BigInt::from_i128(sign * mantissa as i128);
+        if exponent >= 0 {
+            from_big_integer(numer.shift(exponent as i64))
+        } else {
+            normalize_big(numer, BigInt::from_i128(1).shift(-exponent as i64))
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn numerator(x: Exp) -> Exp {
+        match x {
+            Exp::Integer(_) | Exp::BigInteger(_) => x,
+            Exp::Rational(n, _d) => Exp::Integer(n),
+            Exp::BigRational(n, _d) => from_big_integer(BigInt::clone(&n)),
+            Exp::FloatNumber(_x) => exact_to_inexact(numerator(inexact_to_exact(x))),
+            _ => panic!("wrong type for numerator!"),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn denominator(x: Exp) -> Exp {
+        match x {
+            Exp::Integer(_) | Exp::BigInteger(_) => Exp::Integer(1),
+            Exp::Rational(_n, d) => Exp::Integer(d),
+            Exp::BigRational(_n, d) => from_big_integer(BigInt::clone(&d)),
+            Exp::FloatNumber(_x) => exact_to_inexact(denominator(inexact_to_exact(x))),
+            _ => panic!("wrong type for denominator!"),
+        }
+    }
+
+    /// (rationalize x y) returns the simplest rational number differing
+    /// from x by no more than y, the result is inexact if x or y is.
+    #[allow(dead_code)]
+    pub fn rationalize(x: Exp, y: Exp) -> Exp {
+        if is_complex(&x) || is_complex(&y) {
+            panic!("wrong type for rationalize!");
+        }
+        let inexact = !is_exact(&x) || !is_exact(&y);
+        let (a, b) = big_exact_parts(&inexact_to_exact(x)).unwrap();
+        let (c, d) = big_exact_parts(&inexact_to_exact(y)).unwrap();
+        let c = c.abs();
+        let lo = (a.multiply(&d).substract(&c.multiply(&b)), b.multiply(&d));
+        let hi = (a.multiply(&d).add(&c.multiply(&b)), b.multiply(&d));
+        let zero = BigInt::from_i128(0);
+        let (n, m) = if lo.0 > zero {
+            simplest_between(lo, hi)
+        } else if hi.0 < zero {
+            let (n, m) = simplest_between((hi.0.negate(), hi.1), (lo.0.negate(), lo.1));
+            (n.negate(), m)
+        } else {
+            (zero, BigInt::from_i128(1))
+        };
+        let result = normalize_big(n, m);
+        if inexact {
+            exact_to_inexact(result)
+        } else {
+            result
+        }
+    }
+
+    // simplest rational in [lo, hi] where 0 < lo <= hi, by continued fractions
+    fn simplest_between(lo: (BigInt, BigInt), hi: (BigInt, BigInt)) -> (BigInt, BigInt) {
+        let one = BigInt::from_i128(1);
+        let (floor, rest) = lo.0.divide(&lo.1);
+        if rest == BigInt::from_i128(0) {
+            (floor, one)
+        } else if floor < hi.0.divide(&hi.1).0 {
+            (floor.add(&one), one)
+        } else {
+            let (n, m) = simplest_between(
+                (hi.1.clone(), hi.0.substract(&floor.multiply(&hi.1))),
+                (lo.1.clone(), lo.0.substract(&floor.multiply(&lo.1))),
+            );
+            (floor.multiply(&n).add(&m), n)
+        }
+    }
+
+    // the largest r with r * r <= n
+    fn integer_sqrt(n: i128) -> i128 {
+        let mut r = (n as f64).sqrt() as i128;
+        while r * r > n {
+            r -= 1;
+        }
+        while (r + 1) * (r + 1) <= n {
+            r += 1;
+        }
+        r
+    }
+
+    // Newton's method from above, it stops at the floor of the root
+    fn big_integer_sqrt(n: &BigInt) -> BigInt {
+        let zero = BigInt::from_i128(0);
+        if *n == zero {
+            return zero;
+        }
+        let mut x = BigInt::from_i128(1).shift(n.integer_length().div_ceil(2) as i64);
+        loop {
+            let y = x.add(&n.divide(&x).0).shift(-1);
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    fn perfect_sqrt(n: &BigInt) -> Option<BigInt> {
+        let r = big_integer_sqrt(n);
+        if r.multiply(&r) == *n {
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    /// The principal square root, exact whenever an exact argument is
+    /// a perfect square, so (sqrt -1) is the exact +i.
+    #[allow(dead_code)]
+    pub fn sqrt(x: Exp) -> Exp {
+        match x {
+            Exp::Complex(real, imag) => {
+                let re = to_float(&real, "sqrt");
+                let im = to_float(&imag, "sqrt");
+                let m = re.hypot(im);
+                let s = ((m - re) / 2.0).sqrt();
+                make_rectangular(
+                    Exp::FloatNumber(((m + re) / 2.0).sqrt()),
+                    Exp::FloatNumber(if im < 0.0 { -s } else { s }),
+                )
+            }
+            Exp::FloatNumber(f) => {
+                if f < 0.0 {
+                    make_rectangular(Exp::Integer(0), Exp::FloatNumber((-f).sqrt()))
+                } else {
+                    Exp::FloatNumber(f.sqrt())
+                }
+            }
+            _ => match big_exact_parts(&x) {
+                Some((n, d)) => {
+                    if n.is_negative() {
+                        make_rectangular(Exp::Integer(0), sqrt(negate(x)))
+                    } else {
+                        match (perfect_sqrt(&n), perfect_sqrt(&d)) {
+                            (Some(a), Some(b)) => normalize_big(a, b),
+                            _ => Exp::FloatNumber(to_float(&x, "sqrt").sqrt()),
+                        }
+                    }
+                }
+                None => panic!("wrong type for sqrt!"),
+            },
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn exp(x: Exp) -> Exp {
+        match x {
+            Exp::Complex(real, imag) => {
+                let m = to_float(&real, "exp").exp();
+                let a = to_float(&imag, "exp");
+                make_rectangular(Exp::FloatNumber(m * a.cos()), Exp::FloatNumber(m * a.sin()))
+            }
+            _ => {
+                if is_exact_zero(&x) {
+                    Exp::Integer(1)
+                } else {
+                    Exp::FloatNumber(to_float(&x, "exp").exp())
+                }
+            }
+        }
+    }
+
+    /// The natural logarithm, negative and complex arguments take the
+    /// principal branch: log z = log|z| + i angle(z).
+    #[allow(dead_code)]
+    pub fn log(x: Exp) -> Exp {
+        match x {
+            Exp::Complex(real, imag) => {
+                let re = to_float(&real, "log");
+                let im = to_float(&imag, "log");
+                make_rectangular(
+                    Exp::FloatNumber(re.hypot(im).ln()),
+                    Exp::FloatNumber(im.atan2(re)),
+                )
+            }
+            _ => {
+                if x == Exp::Integer(1) {
+                    Exp::Integer(0)
+                } else {
+                    let f = to_float(&x, "log");
+                    if f < 0.0 {
+                        make_rectangular(
+                            Exp::FloatNumber((-f).ln()),
+                            Exp::FloatNumber(std::f64::consts::PI),
+                        )
+                    } else {
+                        Exp::FloatNumber(f.ln())
+                    }
+                }
+            }
+        }
+    }
+
+    /// (exact-integer-sqrt k) => s and r with s * s + r = k
+    #[allow(dead_code)]
+    pub fn exact_integer_sqrt(x: Exp) -> (Exp, Exp) {
+        match x {
+            Exp::Integer(n) if n >= 0 => {
+                let s = integer_sqrt(n as i128);
+                (normalize(s, 1), normalize(n as i128 - s * s, 1))
+            }
+            Exp::BigInteger(n) if !n.is_negative() => {
+                let s = big_integer_sqrt(&n);
+                let r = n.substract(&s.multiply(&s));
+                (from_big_integer(s), from_big_integer(r))
+            }
+            _ => panic!("wrong type for exact-integer-sqrt!"),
+        }
+    }
+
+    /// The unary minus, (- 0.0) is -0.0 where 0 - 0.0 would be 0.0.
+    #[allow(dead_code)]
+    pub fn negate(x: Exp) -> Exp {
+        match x {
+            Exp::FloatNumber(f) => Exp::FloatNumber(-f),
+            Exp::Complex(real, imag) => make_rectangular(negate(*real), negate(*imag)),
+            _ if is_exact(&x) => substract(Exp::Integer(0), x),
+            _ => panic!("wrong type for -!"),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn abs(x: Exp) -> Exp {
+        match x {
+            Exp::Integer(n) => normalize((n as i128).abs(), 1),
+            Exp::BigInteger(n) => Exp::BigInteger(Rc::new(n.abs())),
+            Exp::Rational(n, d) => normalize((n as i128).abs(), d as i128),
+            Exp::BigRational(n, d) => Exp::BigRational(Rc::new(n.abs()), d),
+            Exp::FloatNumber(f) => Exp::FloatNumber(f.abs()),
+            _ => panic!("wrong type for abs!"),
+        }
+    }
+
+    /// base raised to power, exact when an exact or complex base is
+    /// raised to an exact integer.
+    #[allow(dead_code)]
+    pub fn expt(base: Exp, power: Exp) -> Exp {
+        match power {
+            Exp::Integer(k) if is_exact(&base) || is_complex(&base) => {
+                // square and multiply
+                let mut result = Exp::Integer(1);
+                let mut square = base;
+                let mut bits = k.unsigned_abs();
+                while bits > 0 {
+                    if bits & 1 == 1 {
+                        result = multiply(result, square.clone());
+                    }
+                    bits >>= 1;
+                    if bits > 0 {
+                        square = multiply(square.clone(), square);
+                    }
+                }
+                if k < 0 {
+                    divide(Exp::Integer(1), result)
+                } else {
+                    result
+                }
+            }
+            _ if is_exact_zero(&base) => Exp::Integer(0),
+            _ if is_complex(&base) || is_complex(&power) => exp(multiply(power, log(base))),
+            _ => {
+                let b = to_float(&base, "expt");
+                let p = to_float(&power, "expt");
+                if b < 0.0 && p.fract() != 0.0 {
+                    exp(multiply(power, log(base)))
+                } else {
+                    Exp::FloatNumber(b.powf(p))
+                }
+            }
+        }
+    }
+
+    /// sin, cos, tan, asin, acos and atan on real numbers, an exact zero
+    /// stays exact where the function maps zero to zero.
+    #[allow(dead_code)]
+    pub fn trigonometric(x: Exp, name: &str) -> Exp {
+        let f = to_float(&x, name);
+        let result = match name {
+            "sin" => f.sin(),
+            "cos" => f.cos(),
+            "tan" => f.tan(),
+            "asin" => f.asin(),
+            "acos" => f.acos(),
+            "atan" => f.atan(),
+            _ => panic!("unknown function {}!", name),
+        };
+        if is_exact_zero(&x) && result == 0.0 {
+            Exp::Integer(0)
+        } else {
+            Exp::FloatNumber(result)
+        }
+    }
+
+    /// (atan y x), the angle of the point (x, y)
+    #[allow(dead_code)]
+    pub fn atan2(y: Exp, x: Exp) -> Exp {
+        Exp::FloatNumber(to_float(&y, "atan").atan2(to_float(&x, "atan")))
+    }
+
+    // floor, ceiling, truncate and round keep exactness, an exact
+    // number is rounded as numerator/denominator
+    fn round_with(
+        x: Exp,
+        name: &str,
+        inexact: fn(f64) -> f64,
+        exact: fn(&BigInt, &BigInt) -> BigInt,
+    ) -> Exp {
+        match x {
+            Exp::Integer(_) | Exp::BigInteger(_) => x,
+            Exp::Rational(_, _) | Exp::BigRational(_, _) => {
+                let (n, d) = big_exact_parts(&x).unwrap();
+                from_big_integer(exact(&n, &d))
+            }
+            Exp::FloatNumber(f) => Exp::FloatNumber(inexact(f)),
+            _ => panic!("wrong type for {}!", name),
+        }
+    }
+
+    // the floor of n/d for a positive d
+    fn floor_quotient(n: &BigInt, d: &BigInt) -> BigInt {
+        let (q, r) = n.divide(d);
+        if r.is_negative() {
+            q.substract(&BigInt::from_i128(1))
+        } else {
+            q
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn floor(x: Exp) -> Exp {
+        round_with(x, "floor", f64::floor, floor_quotient)
+    }
+
+    #[allow(dead_code)]
+    pub fn ceiling(x: Exp) -> Exp {
+        round_with(x, "ceiling", f64::ceil, |n, d| {
+            floor_quotient(&n.negate(), d).negate()
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn truncate(x: Exp) -> Exp {
+        round_with(x, "truncate", f64::trunc, |n, d| n.divide(d).0)
+    }
+
+    /// Rounds to the nearest integer, to the even one on a tie.
+    #[allow(dead_code)]
+    pub fn round(x: Exp) -> Exp {
+        round_with(x, "round", f64::round_ties_even, |n, d| {
+            let floor = floor_quotient(n, d);
+            let twice_rest = n.substract(&floor.multiply(d)).shift(1);
+            if twice_rest > *d || (twice_rest == *d && floor.is_odd()) {
+                floor.add(&BigInt::from_i128(1))
+            } else {
+                floor
+            }
+        })
+    }
+
+    // an integer operand, which may be an inexact integer such as 4.0,
+    // and whether it was inexact
+    fn integer_operand(x: &Exp, operation: &str) -> (i64, bool) {
+        match x {
+            Exp::Integer(n) => (*n, false),
+            Exp::FloatNumber(f) if f.fract() == 0.0 && f.abs() < 9.2e18 => (*f as i64, true),
+            _ => panic!("wrong type for {}!", operation),
+        }
+    }
+
+    fn integer_result(n: i128, inexact: bool) -> Exp {
+        if inexact {
+            Exp::FloatNumber(n as f64)
+        } else {
+            from_big_integer(BigInt::from_i128(n))
+        }
+    }
+
+    /// quotient, remainder and modulo, and the floor and truncate
+    /// quotients of floor/ and truncate/.
+    #[allow(dead_code)]
+    pub fn integer_division(lhs: Exp, rhs: Exp, operation: &str) -> Exp {
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            return big_integer_division(a, b, operation);
+        }
+        let (a, a_inexact) = integer_operand(&lhs, operation);
+        let (b, b_inexact) = integer_operand(&rhs, operation);
+        if b == 0 {
+            panic!("divide by zero!");
+        }
+        let (a, b) = (a as i128, b as i128);
+        let result = match operation {
+            "quotient" | "truncate-quotient" => a / b,
+            "remainder" | "truncate-remainder" => a % b,
+            "floor-quotient" => {
+                let q = a / b;
+                if a % b != 0 && (a < 0) != (b < 0) {
+                    q - 1
+                } else {
+                    q
+                }
+            }
+            "modulo" | "floor-remainder" => {
+                let r = a % b;
+                if r != 0 && (r < 0) != (b < 0) {
+                    r + b
+                } else {
+                    r
+                }
+            }
+            _ => panic!("unknown integer division {}!", operation),
+        };
+        integer_result(result, a_inexact || b_inexact)
+    }
+
+    fn big_integer_division(a: BigInt, b: BigInt, operation: &str) -> Exp {
+        let (q, r) = a.divide(&b);
+        let zero = BigInt::from_i128(0);
+        let result = match operation {
+            "quotient" | "truncate-quotient" => q,
+            "remainder" | "truncate-remainder" => r,
+            "floor-quotient" if r != zero && a.is_negative() != b.is_negative() => {
+                q.substract(&BigInt::from_i128(1))
+            }
+            "floor-quotient" => q,
+            "modulo" | "floor-remainder" if r != zero && r.is_negative() != b.is_negative() => {
+                r.add(&b)
+            }
+            "modulo" | "floor-remainder" => r,
+            _ => panic!("unknown integer division {}!", operation),
+        };
+        from_big_integer(result)
+    }
+
+    fn big_gcd(a: &BigInt, b: &BigInt) -> BigInt {
+        let zero = BigInt::from_i128(0);
+        let (mut a, mut b) = (a.abs(), b.abs());
+        while b != zero {
+            let r = a.divide(&b).1;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    #[allow(dead_code)]
+    pub fn integer_gcd(lhs: Exp, rhs: Exp) -> Exp {
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            return from_big_integer(big_gcd(&a, &b));
+        }
+        let (a, a_inexact) = integer_operand(&lhs, "gcd");
+        let (b, b_inexact) = integer_operand(&rhs, "gcd");
+        integer_result(gcd_i128(a as i128, b as i128), a_inexact || b_inexact)
+    }
+
+    #[allow(dead_code)]
+    pub fn integer_lcm(lhs: Exp, rhs: Exp) -> Exp {
+        if let Some((a, b)) = big_operands(&lhs, &rhs) {
+            let zero = BigInt::from_i128(0);
+            if a == zero || b == zero {
+                return Exp::Integer(0);
+            }
+            return from_big_integer(a.divide(&big_gcd(&a, &b)).0.multiply(&b).abs());
+        }
+        let (a, a_inexact) = integer_operand(&lhs, "lcm");
+        let (b, b_inexact) = integer_operand(&rhs, "lcm");
+        let (a, b) = (a as i128, b as i128);
+        let lcm = if a == 0 || b == 0 {
+            0
+        } else {
+            (a / gcd_i128(a, b) * b).abs()
+        };
+        integer_result(lcm, a_inexact || b_inexact)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_odd(x: &Exp) -> bool {
+        if let Exp::BigInteger(n) = x {
+            return n.is_odd();
+        }
+        integer_operand(x, "odd?").0 % 2 != 0
+    }
+
+    // an exact integer operand of a bitwise operation
+    fn bitwise_operand(x: &Exp, operation: &str) -> BigInt {
+        match to_big_integer(x) {
+            Some(n) => n,
+            None => panic!("wrong type for {}!", operation),
+        }
+    }
+
+    /* bitwise operations of SRFI 151 see an integer as its infinite
+     * two's complement, so a negative one has infinitely many 1 bits */
+    #[allow(dead_code)]
+    pub fn bitwise_and(lhs: Exp, rhs: Exp) -> Exp {
+        match (&lhs, &rhs) {
+            (Exp::Integer(a), Exp::Integer(b)) => Exp::Integer(a & b),
+            _ => from_big_integer(
+                bitwise_operand(&lhs, "bitwise-and").and(&bitwise_operand(&rhs, "bitwise-and")),
+            ),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn bitwise_or(lhs: Exp, rhs: Exp) -> Exp {
+        match (&lhs, &rhs) {
+            (Exp::Integer(a), Exp::Integer(b)) => Exp::Integer(a | b),
+            _ => from_big_integer(
+                bitwise_operand(&lhs, "bitwise-or").or(&bitwise_operand(&rhs, "bitwise-or")),
+            ),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn bitwise_xor(lhs: Exp, rhs: Exp) -> Exp {
+        match (&lhs, &rhs) {
+            (Exp::Integer(a), Exp::Integer(b)) => Exp::Integer(a ^ b),
+            _ => from_big_integer(
+                bitwise_operand(&lhs, "bitwise-xor").xor(&bitwise_operand(&rhs, "bitwise-xor")),
+            ),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn bitwise_not(x: Exp) -> Exp {
+        match x {
+            Exp::Integer(n) => Exp::Integer(!n),
+            _ => from_big_integer(bitwise_operand(&x, "bitwise-not").not()),
+        }
+    }
+
+    /// (arithmetic-shift n k) is n * 2^k, rounded toward negative
+    /// infinity when k is negative.
+    #[allow(dead_code)]
+    pub fn arithmetic_shift(x: Exp, count: Exp) -> Exp {
+        let k = match count {
+            Exp::Integer(k) => k,
+            _ => panic!("wrong type for arithmetic-shift!"),
+        };
+        from_big_integer(bitwise_operand(&x, "arithmetic-shift").shift(k))
+    }
+
+    #[allow(dead_code)]
+    pub fn bit_count(x: Exp) -> Exp {
+        Exp::Integer(bitwise_operand(&x, "bit-count").bit_count() as i64)
+    }
+
+    #[allow(dead_code)]
+    pub fn integer_length(x: Exp) -> Exp {
+        Exp::Integer(bitwise_operand(&x, "integer-length").integer_length() as i64)
+    }
+
+    /// (bit-set? index n), bit 0 being the least significant one.
+    #[allow(dead_code)]
+    pub fn is_bit_set(index: Exp, x: Exp) -> bool {
+        match index {
+            Exp::Integer(k) if k >= 0 => bitwise_operand(&x, "bit-set?").is_bit_set(k as u64),
+            _ => panic!("wrong type for bit-set?!"),
+        }
+    }
+
+    /// Floats are printed in the shortest form that reads back to the
+    /// same value, always with a decimal point, e.g. 3.0 or 1.5e-7.
+    #[allow(dead_code)]
+    pub fn float_to_string(f: f64) -> String {
+        if f.is_nan() {
+            "+nan.0".to_string()
+        } else if f.is_infinite() {
+            if f > 0.0 {
+                "+inf.0".to_string()
+            } else {
+                "-inf.0".to_string()
+            }
+        } else {
+            let s = format!("{:?}", f);
+            match s.split_once('e') {
+                Some((mantissa, exponent)) if !mantissa.contains('.') => {
+                    format!("{}.0e{}", mantissa, exponent)
+                }
+                _ => s,
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn number_to_string(x: &Exp) -> String {
+        match x {
+            Exp::Integer(n) => n.to_string(),
+            Exp::BigInteger(n) => n.to_string(),
+            Exp::Rational(n, d) => format!("{}/{}", n, d),
+            Exp::BigRational(n, d) => format!("{}/{}", n, d),
+            Exp::FloatNumber(f) => float_to_string(*f),
+            Exp::Complex(real, imag) => {
+                let re = if is_exact_zero(real) {
+                    String::new()
+                } else {
+                    number_to_string(real)
+                };
+                let im = match &**imag {
+                    Exp::Integer(1) => "+".to_string(),
+                    Exp::Integer(-1) => "-".to_string(),
+                    _ => {
+                        let s = number_to_string(imag);
+                        if s.starts_with('-') || s.starts_with('+') {
+                            s
+                        } else {
+                            format!("+{}", s)
+                        }
+                    }
+                };
+                format!("{}{}i", re, im)
+            }
+            _ => panic!("not a number!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::number::*;
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+
+    #[test]
+    fn test_make_rational() {
+        assert_eq!(make_rational(6, 4), Exp::Rational(3, 2));
+        assert_eq!(make_rational(6, 3), Exp::Integer(2));
+        assert_eq!(make_rational(1, -3), Exp::Rational(-1, 3));
+        assert_eq!(gcd(12, -18), 6);
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let third = make_rational(1, 3);
+        let sixth = make_rational(1, 6);
+        assert_eq!(add(third.clone(), sixth.clone()), Exp::Rational(1, 2));
+        assert_eq!(substract(third.clone(), third.clone()), Exp::Integer(0));
+        assert_eq!(multiply(third.clone(), Exp::Integer(3)), Exp::Integer(1));
+        assert_eq!(divide(Exp::Integer(6), Exp::Integer(3)), Exp::Integer(2));
+        assert_eq!(divide(Exp::Integer(1), Exp::Integer(3)), third);
+        assert_eq!(
+            add(third, Exp::FloatNumber(1.0)),
+            Exp::FloatNumber(1.0 + 1.0 / 3.0)
+        );
+        assert!(greater_than(&Exp::Rational(1, 2), &Exp::Rational(1, 3)));
+        assert!(!greater_than(&Exp::Rational(-1, 2), &Exp::Integer(0)));
+        assert_eq!(negate(Exp::Rational(1, 3)), Exp::Rational(-1, 3));
+        assert_eq!(
+            negate(Exp::Integer(i64::MIN)),
+            multiply(Exp::Integer(i64::MIN), Exp::Integer(-1))
+        );
+        // the sign of a zero float is kept
+        match negate(Exp::FloatNumber(0.0)) {
+            Exp::FloatNumber(f) => assert!(f == 0.0 && f.is_sign_negative()),
+            _ => panic!("not a float!"),
+        }
+    }
+
+    #[test]
+    fn test_exactness_conversion() {
+        assert_eq!(
+            exact_to_inexact(Exp::Rational(1, 4)),
+            Exp::FloatNumber(0.25)
+        );
+        assert_eq!(
+            inexact_to_exact(Exp::FloatNumber(0.25)),
+            Exp::Rational(1, 4)
+        );
+        assert_eq!(inexact_to_exact(Exp::FloatNumber(-3.0)), Exp::Integer(-3));
+        assert_eq!(numerator(make_rational(6, 4)), Exp::Integer(3));
+        assert_eq!(denominator(make_rational(6, 4)), Exp::Integer(2));
+        assert_eq!(denominator(Exp::FloatNumber(0.5)), Exp::FloatNumber(2.0));
+        // the denominator of 1e-20 is 2^119
+        let tiny = inexact_to_exact(Exp::FloatNumber(1e-20));
+        assert_eq!(
+            number_to_string(&denominator(tiny.clone())),
+            "664613997892457936451903530140172288"
+        );
+        assert_eq!(exact_to_inexact(tiny), Exp::FloatNumber(1e-20));
+        assert_eq!(
+            exact_to_inexact(inexact_to_exact(Exp::FloatNumber(5e-324))),
+            Exp::FloatNumber(5e-324)
+        );
+    }
+
+    #[test]
+    fn test_rationalize() {
+        assert_eq!(
+            rationalize(make_rational(3, 10), make_rational(1, 10)),
+            Exp::Rational(1, 3)
+        );
+        assert_eq!(
+            rationalize(make_rational(-3, 10), make_rational(1, 10)),
+            Exp::Rational(-1, 3)
+        );
+        assert_eq!(
+            rationalize(Exp::Integer(1), make_rational(1, 2)),
+            Exp::Integer(1)
+        );
+        assert_eq!(
+            rationalize(Exp::FloatNumber(0.3), make_rational(1, 10)),
+            Exp::FloatNumber(1.0 / 3.0)
+        );
+    }
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let i = make_rectangular(Exp::Integer(0), Exp::Integer(1));
+        let z = make_rectangular(Exp::Integer(3), Exp::Integer(4));
+        assert_eq!(multiply(i.clone(), i.clone()), Exp::Integer(-1));
+        assert_eq!(
+            add(z.clone(), i.clone()),
+            make_rectangular(Exp::Integer(3), Exp::Integer(5))
+        );
+        assert_eq!(divide(z.clone(), z.clone()), Exp::Integer(1));
+        assert_eq!(magnitude(z.clone()), Exp::Integer(5));
+        assert_eq!(real_part(z.clone()), Exp::Integer(3));
+        assert_eq!(imag_part(Exp::Rational(1, 2)), Exp::Integer(0));
+        assert_eq!(
+            make_polar(Exp::Integer(2), Exp::Integer(0)),
+            Exp::Integer(2)
+        );
+    }
+
+    #[test]
+    fn test_sqrt_exp_log() {
+        assert_eq!(
+            sqrt(Exp::Integer(-1)),
+            make_rectangular(Exp::Integer(0), Exp::Integer(1))
+        );
+        assert_eq!(sqrt(Exp::Integer(16)), Exp::Integer(4));
+        assert_eq!(sqrt(Exp::Rational(9, 4)), Exp::Rational(3, 2));
+        assert_eq!(sqrt(Exp::Integer(2)), Exp::FloatNumber(2.0f64.sqrt()));
+        // a big perfect square has an exact root
+        let big = expt(Exp::Integer(10), Exp::Integer(40));
+        assert_eq!(sqrt(big.clone()), expt(Exp::Integer(10), Exp::Integer(20)));
+        assert_eq!(
+            sqrt(divide(Exp::Integer(1), big.clone())),
+            divide(Exp::Integer(1), expt(Exp::Integer(10), Exp::Integer(20)))
+        );
+        assert_eq!(
+            sqrt(negate(big)),
+            make_rectangular(Exp::Integer(0), expt(Exp::Integer(10), Exp::Integer(20)))
+        );
+        assert_eq!(exp(Exp::Integer(0)), Exp::Integer(1));
+        assert_eq!(log(Exp::Integer(1)), Exp::Integer(0));
+        assert_eq!(
+            imag_part(log(Exp::Integer(-1))),
+            Exp::FloatNumber(std::f64::consts::PI)
+        );
+    }
+
+    #[test]
+    fn test_number_to_string() {
+        let i = make_rectangular(Exp::Integer(0), Exp::Integer(1));
+        assert_eq!(number_to_string(&i), "+i");
+        assert_eq!(
+            number_to_string(&make_rectangular(Exp::Integer(3), Exp::Integer(-4))),
+            "3-4i"
+        );
+        assert_eq!(
+            number_to_string(&make_rectangular(Exp::Rational(1, 2), Exp::Integer(-1))),
+            "1/2-i"
+        );
+    }
+
+    #[test]
+    fn test_float_to_string() {
+        assert_eq!(float_to_string(3.0), "3.0");
+        assert_eq!(float_to_string(0.1), "0.1");
+        assert_eq!(float_to_string(1e20), "1.0e20");
+        assert_eq!(float_to_string(1.5e-7), "1.5e-7");
+        assert_eq!(float_to_string(f64::INFINITY), "+inf.0");
+        assert_eq!(float_to_string(f64::NEG_INFINITY), "-inf.0");
+        assert_eq!(float_to_string(f64::NAN), "+nan.0");
+        assert_eq!(
+            divide(Exp::FloatNumber(1.0), Exp::FloatNumber(0.0)),
+            Exp::FloatNumber(f64::INFINITY)
+        );
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let big = arithmetic_shift(Exp::Integer(1), Exp::Integer(64));
+        assert_eq!(number_to_string(&big), "18446744073709551616");
+        assert_eq!(
+            multiply(Exp::Integer(i64::MAX), Exp::Integer(2)),
+            substract(big.clone(), Exp::Integer(2))
+        );
+        assert_eq!(substract(big.clone(), big.clone()), Exp::Integer(0));
+        assert_eq!(
+            divide(big.clone(), Exp::Integer(1 << 32)),
+            Exp::Integer(1 << 32)
+        );
+        assert_eq!(
+            integer_division(big.clone(), Exp::Integer(-3), "modulo"),
+            Exp::Integer(-2)
+        );
+        assert!(greater_than(&big, &Exp::Integer(i64::MAX)));
+        assert_eq!(
+            inexact_to_exact(Exp::FloatNumber(1e19)),
+            multiply(Exp::Integer(10_000_000_000), Exp::Integer(1_000_000_000))
+        );
+        assert_eq!(
+            exact_to_inexact(big.clone()),
+            Exp::FloatNumber(18446744073709551616.0)
+        );
+        assert!(!is_odd(&big));
+        assert_eq!(integer_gcd(big.clone(), Exp::Integer(-6)), Exp::Integer(2));
+        assert_eq!(
+            integer_gcd(big.clone(), multiply(big.clone(), Exp::Integer(3))),
+            big
+        );
+        assert_eq!(
+            integer_lcm(big.clone(), Exp::Integer(3)),
+            multiply(big.clone(), Exp::Integer(3))
+        );
+        assert_eq!(integer_lcm(big.clone(), Exp::Integer(0)), Exp::Integer(0));
+        assert_eq!(
+            exact_integer_sqrt(big.clone()),
+            (Exp::Integer(1 << 32), Exp::Integer(0))
+        );
+        assert_eq!(
+            exact_integer_sqrt(add(big.clone(), Exp::Integer(-1))),
+            (Exp::Integer((1 << 32) - 1), Exp::Integer((1 << 33) - 2))
+        );
+        // a rational whose parts do not fit in an i64 gets big parts
+        let third = add(make_rational(1, 3), Exp::Integer(i64::MAX));
+        assert_eq!(number_to_string(&third), "27670116110564327422/3");
+        assert_eq!(
+            substract(third.clone(), Exp::Integer(i64::MAX)),
+            Exp::Rational(1, 3)
+        );
+        assert!(greater_than(&third, &Exp::Integer(i64::MAX)));
+        assert_eq!(floor(third.clone()), Exp::Integer(i64::MAX));
+        assert_eq!(round(third.clone()), Exp::Integer(i64::MAX));
+        assert_eq!(
+            number_to_string(&ceiling(third.clone())),
+            "9223372036854775808"
+        );
+        assert_eq!(denominator(third.clone()), Exp::Integer(3));
+        assert_eq!(
+            rationalize(third.clone(), make_rational(1, 2)),
+            Exp::Integer(i64::MAX)
+        );
+        assert_eq!(
+            number_to_string(&multiply(third.clone(), make_rational(3, 2))),
+            "13835058055282163711"
+        );
+        // a big integer mixed with a rational or divided unevenly is exact
+        assert_eq!(
+            number_to_string(&divide(big.clone(), Exp::Integer(3))),
+            "18446744073709551616/3"
+        );
+        assert_eq!(
+            number_to_string(&add(make_rational(1, 2), big.clone())),
+            "36893488147419103233/2"
+        );
+        assert_eq!(
+            multiply(big.clone(), make_rational(1, 1 << 62)),
+            Exp::Integer(4)
+        );
+        assert!(greater_than(&big, &make_rational(7, 2)));
+        assert_eq!(
+            add(big.clone(), Exp::FloatNumber(0.5)),
+            Exp::FloatNumber(18446744073709551616.5)
+        );
+    }
+
+    #[test]
+    fn test_bitwise() {
+        assert_eq!(
+            bitwise_and(Exp::Integer(12), Exp::Integer(10)),
+            Exp::Integer(8)
+        );
+        assert_eq!(
+            bitwise_or(Exp::Integer(12), Exp::Integer(10)),
+            Exp::Integer(14)
+        );
+        assert_eq!(
+            bitwise_xor(Exp::Integer(12), Exp::Integer(10)),
+            Exp::Integer(6)
+        );
+        assert_eq!(bitwise_not(Exp::Integer(12)), Exp::Integer(-13));
+        assert_eq!(
+            arithmetic_shift(Exp::Integer(-7), Exp::Integer(-1)),
+            Exp::Integer(-4)
+        );
+        let big = arithmetic_shift(Exp::Integer(-1), Exp::Integer(70));
+        assert_eq!(bitwise_and(big.clone(), Exp::Integer(-1)), big);
+        assert_eq!(
+            arithmetic_shift(big.clone(), Exp::Integer(-70)),
+            Exp::Integer(-1)
+        );
+        assert_eq!(bit_count(Exp::Integer(-1)), Exp::Integer(0));
+        assert_eq!(bit_count(Exp::Integer(13)), Exp::Integer(3));
+        assert_eq!(integer_length(big), Exp::Integer(70));
+        assert_eq!(integer_length(Exp::Integer(-8)), Exp::Integer(3));
+        assert!(is_bit_set(Exp::Integer(2), Exp::Integer(4)));
+        assert!(!is_bit_set(Exp::Integer(1), Exp::Integer(4)));
+    }
+
+    #[test]
+    fn test_rounding_and_integer_division() {
+        assert_eq!(floor(Exp::Rational(-7, 2)), Exp::Integer(-4));
+        assert_eq!(ceiling(Exp::Rational(-7, 2)), Exp::Integer(-3));
+        assert_eq!(truncate(Exp::Rational(-7, 2)), Exp::Integer(-3));
+        assert_eq!(round(Exp::Rational(7, 2)), Exp::Integer(4));
+        assert_eq!(round(Exp::Rational(5, 2)), Exp::Integer(2));
+        assert_eq!(round(Exp::FloatNumber(2.5)), Exp::FloatNumber(2.0));
+        assert_eq!(
+            integer_division(Exp::Integer(-7), Exp::Integer(2), "modulo"),
+            Exp::Integer(1)
+        );
+        assert_eq!(
+            integer_division(Exp::Integer(-7), Exp::Integer(2), "remainder"),
+            Exp::Integer(-1)
+        );
+        assert_eq!(
+            integer_division(Exp::Integer(-7), Exp::Integer(2), "floor-quotient"),
+            Exp::Integer(-4)
+        );
+        assert_eq!(
+            integer_division(Exp::FloatNumber(7.0), Exp::Integer(2), "quotient"),
+            Exp::FloatNumber(3.0)
+        );
+        assert_eq!(
+            integer_lcm(Exp::Integer(4), Exp::Integer(-6)),
+            Exp::Integer(12)
+        );
+    }
+
+    #[test]
+    fn test_expt_and_roots() {
+        assert_eq!(expt(Exp::Integer(2), Exp::Integer(10)), Exp::Integer(1024));
+        assert_eq!(
+            expt(Exp::Rational(2, 3), Exp::Integer(-2)),
+            Exp::Rational(9, 4)
+        );
+        assert_eq!(expt(Exp::Integer(0), Exp::Integer(0)), Exp::Integer(1));
+        assert_eq!(
+            expt(Exp::Integer(4), Exp::FloatNumber(0.5)),
+            Exp::FloatNumber(2.0)
+        );
+        assert_eq!(
+            exact_integer_sqrt(Exp::Integer(17)),
+            (Exp::Integer(4), Exp::Integer(1))
+        );
+        assert_eq!(abs(Exp::Rational(-1, 2)), Exp::Rational(1, 2));
+        assert_eq!(trigonometric(Exp::Integer(0), "sin"), Exp::Integer(0));
+        assert_eq!(trigonometric(Exp::Integer(0), "cos"), Exp::FloatNumber(1.0));
+    }
+}
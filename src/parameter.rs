@@ -0,0 +1,68 @@
+#[allow(clippy::module_inception)]
+pub mod parameter {
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A parameter object made by make-parameter. Calling it with no
+    /// arguments returns its current value.
+    #[derive(Debug)]
+    pub struct Parameter {
+        pub value: RefCell<Exp>,
+        pub converter: Option<Exp>,
+    }
+
+    /// Rebinds parameters for the dynamic extent of a parameterize body.
+    /// The old values are restored when the guard is dropped, so they
+    /// come back however the body is left, by returning an error or by a
+    /// panic.
+    #[allow(dead_code)]
+    pub struct ParameterizeGuard {
+        saved: Vec<(Rc<Parameter>, Exp)>,
+    }
+
+    #[allow(dead_code)]
+    impl ParameterizeGuard {
+        pub fn new() -> Self {
+            ParameterizeGuard { saved: vec![] }
+        }
+
+        pub fn bind(&mut self, p: Rc<Parameter>, value: Exp) {
+            let old = p.value.replace(value);
+            self.saved.push((p, old));
+        }
+    }
+
+    impl Drop for ParameterizeGuard {
+        fn drop(&mut self) {
+            // restore in reverse, so a parameter bound twice gets its
+            // outermost value back
+            while let Some((p, old)) = self.saved.pop() {
+                p.value.replace(old);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parameter::{Parameter, ParameterizeGuard};
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_parameterize_guard() {
+        let p = Rc::new(Parameter {
+            value: RefCell::new(Exp::Integer(1)),
+            converter: None,
+        });
+        {
+            let mut guard = ParameterizeGuard::new();
+            guard.bind(p.clone(), Exp::Integer(2));
+            guard.bind(p.clone(), Exp::Integer(3));
+            assert_eq!(*p.value.borrow(), Exp::Integer(3));
+        }
+        assert_eq!(*p.value.borrow(), Exp::Integer(1));
+    }
+}
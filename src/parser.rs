@@ -1,22 +1,31 @@
+#[allow(clippy::module_inception)]
 pub mod parser {
+    use crate::bigint::bigint::BigInt;
+    use crate::port::port::Port;
+    use crate::symbol::symbol::{Symbol, QUOTE};
     use crate::{
         core_of_interpreter::core_of_interpreter::{Exp, Pair},
+        number::number::{from_big_integer, make_big_rational, make_polar, make_rectangular},
         represent::represent::car,
         scheme_list,
-        tool::tools::{append, scheme_cons},
+        tool::tools::{append, list_to_vec, scheme_cons},
     };
+    use std::cell::RefCell;
     use std::fs::File;
     use std::io;
     use std::io::prelude::*;
     use std::io::BufReader;
+    use std::rc::Rc;
 
     #[allow(dead_code)]
-    pub fn read_scheme_programs_from_stdin(p: &mut Vec<String>) -> io::Result<()> {
+    /// Reads lines until they make complete forms or a blank line is
+    /// read. Ok(false) tells that stdin ended before anything was read.
+    pub fn read_scheme_programs_from_stdin(p: &mut Vec<String>) -> io::Result<bool> {
         let stdin = io::stdin();
-
-        for line in stdin.lock().lines() {
-            match line {
-                Ok(line) => {
+        let mut lines = stdin.lock().lines();
+        loop {
+            match lines.next() {
+                Some(Ok(line)) => {
                     if !line.trim().is_empty() {
                         p.push(line);
                         let tokens = tokenize(p);
@@ -27,25 +36,26 @@ pub mod parser {
                         break;
                     }
                 }
-                Err(_e) => break,
+                Some(Err(_e)) => break,
+                None => return Ok(!p.is_empty()),
             }
         }
-        Ok(())
+        Ok(true)
     }
 
     #[allow(dead_code)]
-    pub fn syntax_checker(t: &Vec<String>) -> bool {
+    pub fn syntax_checker(t: &[String]) -> bool {
         let mut iterator = t.iter();
         let mut left_parenthesis = 0;
         let mut right_parenthesis = 0;
         let mut token = iterator.next();
         loop {
             match token {
-                x if x == Some(&("(".to_string())) => {
-                    left_parenthesis = left_parenthesis + 1;
+                Some(x) if x == "(" => {
+                    left_parenthesis += 1;
                 }
-                x if x == Some(&(")".to_string())) => {
-                    right_parenthesis = right_parenthesis + 1;
+                Some(x) if x == ")" => {
+                    right_parenthesis += 1;
                 }
                 Some(_x) => {}
                 None => {
@@ -54,16 +64,17 @@ pub mod parser {
             }
             token = iterator.next();
         }
-        if left_parenthesis == right_parenthesis {
-            true
-        } else {
-            false
-        }
+        left_parenthesis == right_parenthesis
     }
 
     #[allow(dead_code)]
     pub fn read_scheme_programs_from_file(p: &mut Vec<String>) -> io::Result<()> {
-        let f = File::open("scheme.txt")?;
+        read_scheme_programs_from_path("scheme.txt", p)
+    }
+
+    #[allow(dead_code)]
+    pub fn read_scheme_programs_from_path(name: &str, p: &mut Vec<String>) -> io::Result<()> {
+        let f = File::open(name)?;
         let reader = BufReader::new(f);
 
         for line in reader.lines() {
@@ -80,23 +91,155 @@ pub mod parser {
     }
 
     #[allow(dead_code)]
-    pub fn tokenize(p: &mut Vec<String>) -> Vec<String> {
-        let mut ss: Vec<String> = p.into_iter().map(|x| x.replace("(", " ( ")).collect();
-        ss = ss.into_iter().map(|x| x.replace(")", " ) ")).collect();
+    pub fn tokenize(p: &[String]) -> Vec<String> {
         let mut tokens: Vec<String> = vec![];
-        for item in ss.iter() {
-            let mut v = item
-                .trim()
-                .split_whitespace()
-                .collect::<Vec<_>>()
-                .into_iter()
-                .map(|x| x.to_string())
-                .collect();
-            tokens.append(&mut v);
+        let mut token = String::new();
+        let mut chars = p
+            .join("\n")
+            .chars()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '(' | ')' | '\'' => {
+                    flush_token(&mut token, &mut tokens);
+                    tokens.push(c.to_string());
+                }
+                // a string literal is one token, quotes and escapes included
+                '"' => {
+                    flush_token(&mut token, &mut tokens);
+                    token.push(c);
+                    while let Some(c) = chars.next() {
+                        token.push(c);
+                        if c == '\\' {
+                            if let Some(escaped) = chars.next() {
+                                token.push(escaped);
+                            }
+                        } else if c == '"' {
+                            break;
+                        }
+                    }
+                    flush_token(&mut token, &mut tokens);
+                }
+                // comment till the end of line
+                ';' => {
+                    flush_token(&mut token, &mut tokens);
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                // #\( is a character, not a parenthesis
+                '\\' if token == "#" => {
+                    token.push(c);
+                    if let Some(c) = chars.next() {
+                        token.push(c);
+                    }
+                }
+                c if c.is_whitespace() => flush_token(&mut token, &mut tokens),
+                _ => token.push(c),
+            }
         }
+        flush_token(&mut token, &mut tokens);
         tokens
     }
 
+    /// Reads one datum from a port with the same reader as the REPL, or
+    /// returns the eof object when the port has nothing left but
+    /// whitespace and comments.
+    #[allow(dead_code)]
+    pub fn read_from_port(port: &mut dyn Port) -> Exp {
+        let mut text = String::new();
+        // the symbol, number or # syntax being read
+        let mut atom = String::new();
+        let mut depth = 0;
+        while let Some(c) = port.read_char() {
+            let mut closed = false;
+            match c {
+                ';' => {
+                    while let Some(c) = port.read_char() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                    text.push(' ');
+                }
+                '"' => {
+                    text.push(c);
+                    while let Some(c) = port.read_char() {
+                        text.push(c);
+                        if c == '\\' {
+                            if let Some(escaped) = port.read_char() {
+                                text.push(escaped);
+                            }
+                        } else if c == '"' {
+                            break;
+                        }
+                    }
+                    closed = true;
+                }
+                '(' => {
+                    depth += 1;
+                    text.push(c);
+                }
+                ')' => {
+                    if depth == 0 {
+                        panic!("unexpected ) in input!");
+                    }
+                    depth -= 1;
+                    text.push(c);
+                    closed = true;
+                }
+                '\'' => text.push(c),
+                c if c.is_whitespace() => text.push(' '),
+                _ => {
+                    text.push(c);
+                    atom.push(c);
+                    // #\( is a character, not a parenthesis
+                    if atom == "#\\" {
+                        if let Some(c) = port.read_char() {
+                            text.push(c);
+                            atom.push(c);
+                        }
+                    }
+                    // #( and #u8( start a vector and a bytevector
+                    closed = match port.peek_char() {
+                        Some('(') => atom != "#" && atom != "#u8",
+                        Some(next) => !is_atom_char(next),
+                        None => true,
+                    };
+                }
+            }
+            if !is_atom_char(c) {
+                atom.clear();
+            }
+            if closed && depth == 0 {
+                break;
+            }
+        }
+        if depth > 0 {
+            panic!("unexpected end of input!");
+        }
+        if text.trim().is_empty() {
+            return Exp::Eof;
+        }
+        let mut tokens = tokenize(&[text]);
+        build_syntax_tree(&mut tokens)
+    }
+
+    // a character that continues a symbol, number or # syntax
+    fn is_atom_char(c: char) -> bool {
+        !c.is_whitespace() && !['(', ')', '"', ';', '\''].contains(&c)
+    }
+
+    fn flush_token(token: &mut String, tokens: &mut Vec<String>) {
+        if !token.is_empty() {
+            tokens.push(std::mem::take(token));
+        }
+    }
+
     #[allow(dead_code)]
     fn reverse(s: &mut Vec<String>) -> Vec<String> {
         let mut x = vec![];
@@ -116,108 +259,247 @@ pub mod parser {
             Exp::List(Pair::Nil)
         }
     }
+
+    /// Every top-level form of a program, as a list.
+    #[allow(dead_code)]
+    pub fn build_syntax_forms(tokens: &mut Vec<String>) -> Exp {
+        let mut tokens = reverse(tokens);
+        build_syntax_tree_helper(&mut tokens)
+    }
+
+    /// The top-level forms of a file, for load and include.
+    #[allow(dead_code)]
+    pub fn read_forms_from_file(name: &str) -> Exp {
+        let mut programs: Vec<String> = vec![];
+        if let Err(e) = read_scheme_programs_from_path(name, &mut programs) {
+            panic!("cannot open {}: {}", name, e);
+        }
+        let mut tokens = tokenize(&programs);
+        build_syntax_forms(&mut tokens)
+    }
+
     #[allow(dead_code)]
     fn build_syntax_tree_helper(tokens: &mut Vec<String>) -> Exp {
         let mut tree_buffer = Exp::List(Pair::Nil);
         while let Some(t) = tokens.pop() {
             let token = t;
             match token {
-                // head of a Exp::List
-                x if x == "(".to_string() => {
-                    let subtree = build_syntax_tree_helper(tokens);
-                    tree_buffer = append(tree_buffer, scheme_list!(subtree));
-                }
                 // tail of a Exp::List
-                x if x == ")".to_string() => {
+                x if x == ")" => {
                     break;
                 }
-                x if x == "Nil".to_string() => {
-                    tree_buffer = append(tree_buffer, Exp::List(Pair::Nil));
-                }
-                // bool value
-                x if x == "true" => {
-                    tree_buffer = append(tree_buffer, scheme_list!(Exp::Bool(true)));
-                }
-                x if x == "false" => {
-                    tree_buffer = append(tree_buffer, scheme_list!(Exp::Bool(false)));
-                }
-                // symbol value
-                x if is_symbol(&x) => {
-                    tree_buffer = append(tree_buffer, scheme_list!(Exp::Symbol(x)));
-                }
-                // scheme string, for example, "winter is coming!"
-                x if x == "\"".to_string() => {
-                    let s = read_scheme_string(tokens);
-                    tree_buffer = append(tree_buffer, scheme_list!(s));
-                }
-                // scheme quote, for example, 'winter
-                x if x.chars().nth(0) == Some('\'') => {
-                    tree_buffer = append(tree_buffer, scheme_list!(Exp::Quote(x)));
-                }
-                // i32
-                x if is_i32(x.clone()) => {
-                    tree_buffer = append(
-                        tree_buffer,
-                        scheme_list!(Exp::Integer(x.parse::<i32>().unwrap())),
-                    );
-                }
-                // f32
-                x if is_f32(x.clone()) => {
-                    tree_buffer = append(
-                        tree_buffer,
-                        scheme_list!(Exp::FloatNumber(x.parse::<f32>().unwrap())),
-                    );
-                }
-                _ => {
-                    panic!("unknow token!");
+                x => {
+                    let datum = read_datum(x, tokens);
+                    tree_buffer = append(tree_buffer, scheme_list!(datum));
                 }
             }
         }
         tree_buffer
     }
 
-    fn read_scheme_string(_tokens: &mut Vec<String>) -> Exp {
-        Exp::SchemeString("hello world!".to_string())
+    fn read_datum(token: String, tokens: &mut Vec<String>) -> Exp {
+        match token {
+            // head of a Exp::List
+            x if x == "(" => build_syntax_tree_helper(tokens),
+            // scheme quote, for example, 'winter => (quote winter)
+            x if x == "'" => match tokens.pop() {
+                Some(next) => scheme_list!(Exp::Symbol(QUOTE), read_datum(next, tokens)),
+                None => panic!("nothing to quote!"),
+            },
+            // vector, for example, #(1 2 3)
+            x if x == "#" && tokens.last().is_some_and(|next| next == "(") => {
+                tokens.pop();
+                let items = build_syntax_tree_helper(tokens);
+                Exp::Vector(Rc::new(list_to_vec(items)))
+            }
+            // bytevector, for example, #u8(1 2 3)
+            x if x == "#u8" && tokens.last().is_some_and(|next| next == "(") => {
+                tokens.pop();
+                let items = list_to_vec(build_syntax_tree_helper(tokens));
+                let bytes = items
+                    .into_iter()
+                    .map(|x| match x {
+                        Exp::Integer(x) if (0..=255).contains(&x) => x as u8,
+                        _ => panic!("not a byte!"),
+                    })
+                    .collect();
+                Exp::Bytevector(Rc::new(RefCell::new(bytes)))
+            }
+            x if x == "Nil" => Exp::List(Pair::Nil),
+            // bool value
+            x if x == "true" || x == "#t" || x == "#true" => Exp::Bool(true),
+            x if x == "false" || x == "#f" || x == "#false" => Exp::Bool(false),
+            // character, for example, #\a or #\space
+            x if x.starts_with("#\\") => parse_character(&x),
+            // scheme string, for example, "winter is coming!"
+            x if x.starts_with('"') => read_scheme_string(&x),
+            // symbol value
+            x if is_symbol(&x) => Exp::Symbol(Symbol::new(&x)),
+            // i64
+            x if is_i64(x.clone()) => Exp::Integer(x.parse::<i64>().unwrap()),
+            // an integer too large for an i64
+            x if BigInt::parse(&x).is_some() => from_big_integer(BigInt::parse(&x).unwrap()),
+            // rational, for example, 1/3
+            x if is_rational(x.clone()) => parse_rational(x),
+            // f64, including +inf.0, -inf.0 and +nan.0
+            x if is_f64(x.clone()) => Exp::FloatNumber(parse_f64(&x).unwrap()),
+            // complex, for example, 3+4i, -i or 1@2
+            x if is_complex(x.clone()) => parse_complex(&x).unwrap(),
+            _ => {
+                panic!("unknow token!");
+            }
+        }
     }
 
+    fn read_scheme_string(token: &str) -> Exp {
+        if token.len() < 2 || !token.ends_with('"') {
+            panic!("unterminated string!");
+        }
+        let mut s = String::new();
+        let mut chars = token[1..token.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some(c) => s.push(c),
+                    None => panic!("unterminated string!"),
+                }
+            } else {
+                s.push(c);
+            }
+        }
+        Exp::SchemeString(s)
+    }
+
+    fn parse_character(x: &str) -> Exp {
+        let name = &x[2..];
+        match name {
+            "space" => Exp::Character(' '),
+            "newline" => Exp::Character('\n'),
+            "tab" => Exp::Character('\t'),
+            _ if name.chars().count() == 1 => Exp::Character(name.chars().next().unwrap()),
+            _ => panic!("unknow character name!"),
+        }
+    }
+
+    // identifiers start with a letter or one of !$%&*/:<=>?^_~, besides
+    // the peculiar identifiers +, - and ... and those starting with ->
     fn is_symbol(x: &String) -> bool {
-        x.chars().nth(0).unwrap().is_alphabetic()
-            || x == "="
+        let first = x.chars().next().unwrap();
+        first.is_alphabetic()
+            || "!$%&*/:<=>?^_~".contains(first)
             || x == "+"
             || x == "-"
-            || x == "*"
-            || x == "/"
-            || x == ">"
-            || x == "<"
+            || x == "..."
+            || x.starts_with("->")
     }
 
-    fn is_f32(x: String) -> bool {
-        let s = x.parse::<f32>();
-        match s {
-            Ok(_x) => true,
-            _ => false,
+    fn is_f64(x: String) -> bool {
+        parse_f64(&x).is_some()
+    }
+
+    fn parse_f64(x: &str) -> Option<f64> {
+        match x {
+            "+inf.0" => Some(f64::INFINITY),
+            "-inf.0" => Some(f64::NEG_INFINITY),
+            "+nan.0" | "-nan.0" => Some(f64::NAN),
+            // rust also accepts "inf" and "NaN", which are not scheme numbers
+            _ if x.chars().any(|c| c.is_alphabetic() && c != 'e' && c != 'E') => None,
+            _ => x.parse::<f64>().ok(),
         }
     }
 
-    fn is_i32(x: String) -> bool {
-        let s = x.parse::<i32>();
+    #[allow(clippy::match_like_matches_macro)]
+    fn is_i64(x: String) -> bool {
+        let s = x.parse::<i64>();
         match s {
             Ok(_x) => true,
             _ => false,
         }
     }
+
+    fn is_rational(x: String) -> bool {
+        match x.split_once('/') {
+            Some((numer, denom)) => {
+                BigInt::parse(numer).is_some()
+                    && !denom.starts_with('+')
+                    && !denom.starts_with('-')
+                    && BigInt::parse(denom).is_some_and(|d| d != BigInt::from_i128(0))
+            }
+            None => false,
+        }
+    }
+
+    fn parse_rational(x: String) -> Exp {
+        let (numer, denom) = x.split_once('/').unwrap();
+        make_big_rational(BigInt::parse(numer).unwrap(), BigInt::parse(denom).unwrap())
+    }
+
+    fn parse_real(x: &str) -> Option<Exp> {
+        if is_i64(x.to_string()) {
+            Some(Exp::Integer(x.parse::<i64>().unwrap()))
+        } else if let Some(n) = BigInt::parse(x) {
+            Some(from_big_integer(n))
+        } else if is_rational(x.to_string()) {
+            Some(parse_rational(x.to_string()))
+        } else if is_f64(x.to_string()) {
+            Some(Exp::FloatNumber(parse_f64(x).unwrap()))
+        } else {
+            None
+        }
+    }
+
+    fn is_complex(x: String) -> bool {
+        parse_complex(&x).is_some()
+    }
+
+    fn parse_complex(x: &str) -> Option<Exp> {
+        // polar notation, magnitude@angle
+        if let Some((magnitude, angle)) = x.split_once('@') {
+            return match (parse_real(magnitude), parse_real(angle)) {
+                (Some(m), Some(a)) => Some(make_polar(m, a)),
+                _ => None,
+            };
+        }
+        // rectangular notation, the imaginary part starts at the last
+        // sign which is not the sign of an exponent
+        let body = x.strip_suffix('i')?;
+        let split = body
+            .char_indices()
+            .rev()
+            .find(|(i, c)| (*c == '+' || *c == '-') && !body[..*i].ends_with(['e', 'E']))
+            .map(|(i, _c)| i)?;
+        let (real, imag) = body.split_at(split);
+        let real = if real.is_empty() {
+            Some(Exp::Integer(0))
+        } else {
+            parse_real(real)
+        };
+        let imag = match imag {
+            "+" => Some(Exp::Integer(1)),
+            "-" => Some(Exp::Integer(-1)),
+            _ => parse_real(imag),
+        };
+        match (real, imag) {
+            (Some(r), Some(i)) => Some(make_rectangular(r, i)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::parser::*;
     use crate::core_of_interpreter::core_of_interpreter::{Exp, Pair};
+    use crate::scheme_list;
+    use crate::symbol::symbol::Symbol;
     use crate::tool::tools::{append, scheme_cons};
-    use crate::{display::display::pretty_print, scheme_list};
+    use std::rc::Rc;
     #[test]
     fn test_read_scheme_programs() {
         let mut programs: Vec<String> = vec![];
-        read_scheme_programs_from_file(&mut programs);
+        read_scheme_programs_from_file(&mut programs).unwrap();
         let mut item = programs.iter();
         assert_eq!(item.next(), Some(&"(define (fac n)".to_string()));
         assert_eq!(item.next(), Some(&"   (if (= n 1)".to_string()));
@@ -231,8 +513,8 @@ mod tests {
     fn test_tokenize() {
         let mut programs: Vec<String> = vec![];
         let mut tokens: Vec<String> = vec![];
-        read_scheme_programs_from_file(&mut programs);
-        tokens = tokenize(&mut programs);
+        read_scheme_programs_from_file(&mut programs).unwrap();
+        tokens = tokenize(&programs);
         let s = vec![
             "(", "define", "(", "fac", "n", ")", "(", "if", "(", "=", "n", "1", ")", "1", "(", "*",
             "n", "(", "fac", "(", "-", "n", "1", ")", ")", ")", ")", ")",
@@ -243,10 +525,11 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_syntax_checker() {
         let mut programs: Vec<String> = vec![];
-        read_scheme_programs_from_file(&mut programs);
-        let tokens = tokenize(&mut programs);
+        read_scheme_programs_from_file(&mut programs).unwrap();
+        let tokens = tokenize(&programs);
         assert_eq!(syntax_checker(&tokens), true);
     }
 
@@ -254,8 +537,8 @@ mod tests {
     fn test_build_syntax_tree() {
         let mut programs: Vec<String> = vec![];
         let mut tokens: Vec<String> = vec![];
-        read_scheme_programs_from_file(&mut programs);
-        tokens = tokenize(&mut programs);
+        read_scheme_programs_from_file(&mut programs).unwrap();
+        tokens = tokenize(&programs);
         let x = build_syntax_tree(&mut tokens);
         /* test case:
          (define (fac n)
@@ -265,24 +548,27 @@ mod tests {
                        (fac ( - n  1)))))
         */
         let y = scheme_list!(
-            Exp::Symbol("define".to_string()),
-            scheme_list!(Exp::Symbol("fac".to_string()), Exp::Symbol("n".to_string())),
+            Exp::Symbol(Symbol::new("define")),
+            scheme_list!(
+                Exp::Symbol(Symbol::new("fac")),
+                Exp::Symbol(Symbol::new("n"))
+            ),
             scheme_list!(
-                Exp::Symbol("if".to_string()),
+                Exp::Symbol(Symbol::new("if")),
                 scheme_list!(
-                    Exp::Symbol("=".to_string()),
-                    Exp::Symbol("n".to_string()),
+                    Exp::Symbol(Symbol::new("=")),
+                    Exp::Symbol(Symbol::new("n")),
                     Exp::Integer(1)
                 ),
                 Exp::Integer(1),
                 scheme_list!(
-                    Exp::Symbol("*".to_string()),
-                    Exp::Symbol("n".to_string()),
+                    Exp::Symbol(Symbol::new("*")),
+                    Exp::Symbol(Symbol::new("n")),
                     scheme_list!(
-                        Exp::Symbol("fac".to_string()),
+                        Exp::Symbol(Symbol::new("fac")),
                         scheme_list!(
-                            Exp::Symbol("-".to_string()),
-                            Exp::Symbol("n".to_string()),
+                            Exp::Symbol(Symbol::new("-")),
+                            Exp::Symbol(Symbol::new("n")),
                             Exp::Integer(1)
                         )
                     )
@@ -291,4 +577,109 @@ mod tests {
         );
         assert_eq!(x, y);
     }
+
+    #[test]
+    fn test_build_rational() {
+        let programs = vec!["(+ 1/3 -6/4 4/2)".to_string()];
+        let mut tokens = tokenize(&programs);
+        let x = build_syntax_tree(&mut tokens);
+        let y = scheme_list!(
+            Exp::Symbol(Symbol::new("+")),
+            Exp::Rational(1, 3),
+            Exp::Rational(-3, 2),
+            Exp::Integer(2)
+        );
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_build_complex() {
+        let programs = vec!["(+ 3+4i -i 1/2-2.5i 2@0)".to_string()];
+        let mut tokens = tokenize(&programs);
+        let x = build_syntax_tree(&mut tokens);
+        let y = scheme_list!(
+            Exp::Symbol(Symbol::new("+")),
+            Exp::Complex(Box::new(Exp::Integer(3)), Box::new(Exp::Integer(4))),
+            Exp::Complex(Box::new(Exp::Integer(0)), Box::new(Exp::Integer(-1))),
+            Exp::Complex(
+                Box::new(Exp::Rational(1, 2)),
+                Box::new(Exp::FloatNumber(-2.5))
+            ),
+            Exp::Integer(2)
+        );
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_build_float() {
+        let programs = vec!["(+ 0.1 -inf.0 +inf.0 1e3)".to_string()];
+        let mut tokens = tokenize(&programs);
+        let x = build_syntax_tree(&mut tokens);
+        let y = scheme_list!(
+            Exp::Symbol(Symbol::new("+")),
+            Exp::FloatNumber(0.1),
+            Exp::FloatNumber(f64::NEG_INFINITY),
+            Exp::FloatNumber(f64::INFINITY),
+            Exp::FloatNumber(1000.0)
+        );
+        assert_eq!(x, y);
+        let programs = vec!["+nan.0".to_string()];
+        let mut tokens = tokenize(&programs);
+        match build_syntax_tree(&mut tokens) {
+            Exp::FloatNumber(x) => assert!(x.is_nan()),
+            _ => panic!("+nan.0 is not read as a float!"),
+        }
+    }
+
+    #[test]
+    fn test_build_character_and_bool() {
+        let programs = vec!["(list #\\a #\\space #t #false)".to_string()];
+        let mut tokens = tokenize(&programs);
+        let x = build_syntax_tree(&mut tokens);
+        let y = scheme_list!(
+            Exp::Symbol(Symbol::new("list")),
+            Exp::Character('a'),
+            Exp::Character(' '),
+            Exp::Bool(true),
+            Exp::Bool(false)
+        );
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_build_quote_and_string() {
+        let programs = vec!["(f '(a b) 'c \"(a \\\"b\\\")\") ; comment".to_string()];
+        let mut tokens = tokenize(&programs);
+        let x = build_syntax_tree(&mut tokens);
+        let quote = Exp::Symbol(Symbol::new("quote"));
+        let y = scheme_list!(
+            Exp::Symbol(Symbol::new("f")),
+            scheme_list!(
+                quote.clone(),
+                scheme_list!(Exp::Symbol(Symbol::new("a")), Exp::Symbol(Symbol::new("b")))
+            ),
+            scheme_list!(quote, Exp::Symbol(Symbol::new("c"))),
+            Exp::SchemeString("(a \"b\")".to_string())
+        );
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_build_vector() {
+        let programs = vec!["(f #(1 #(2)) '#())".to_string()];
+        let mut tokens = tokenize(&programs);
+        let x = build_syntax_tree(&mut tokens);
+        let y = scheme_list!(
+            Exp::Symbol(Symbol::new("f")),
+            Exp::Vector(Rc::new(vec![
+                Exp::Integer(1),
+                Exp::Vector(Rc::new(vec![Exp::Integer(2)]))
+            ])),
+            scheme_list!(
+                Exp::Symbol(Symbol::new("quote")),
+                Exp::Vector(Rc::new(vec![]))
+            )
+        );
+        assert_eq!(x, y);
+    }
 }
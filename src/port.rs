@@ -0,0 +1,371 @@
+#[allow(clippy::module_inception)]
+pub mod port {
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use crate::parameter::parameter::Parameter;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::fmt;
+    use std::fs::File;
+    use std::io::{self, BufRead, BufReader, BufWriter, Write};
+    use std::rc::{Rc, Weak};
+
+    /// Where Scheme reads from and writes to. An embedder redirects the
+    /// output of a program by implementing Port and installing it with
+    /// set_current_output_port.
+    #[allow(dead_code)]
+    pub trait Port: fmt::Debug {
+        fn is_input(&self) -> bool {
+            false
+        }
+
+        fn is_output(&self) -> bool {
+            false
+        }
+
+        fn write_str(&mut self, _s: &str) {
+            panic!("not an output port!");
+        }
+
+        fn flush(&mut self) {}
+
+        /// None at the end of the input.
+        fn read_char(&mut self) -> Option<char> {
+            panic!("not an input port!");
+        }
+
+        fn peek_char(&mut self) -> Option<char> {
+            panic!("not an input port!");
+        }
+
+        /// Whether read_char would not block.
+        fn char_ready(&mut self) -> bool {
+            true
+        }
+
+        fn close(&mut self) {}
+
+        /// What has been written to a string output port.
+        fn output_string(&self) -> Option<String> {
+            None
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct StdoutPort;
+
+    impl Port for StdoutPort {
+        fn is_output(&self) -> bool {
+            true
+        }
+
+        fn write_str(&mut self, s: &str) {
+            print!("{}", s);
+        }
+
+        fn flush(&mut self) {
+            io::stdout().flush().unwrap();
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct StderrPort;
+
+    impl Port for StderrPort {
+        fn is_output(&self) -> bool {
+            true
+        }
+
+        fn write_str(&mut self, s: &str) {
+            eprint!("{}", s);
+        }
+    }
+
+    /// Reads stdin a line at a time.
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    pub struct StdinPort {
+        buffer: VecDeque<char>,
+    }
+
+    #[allow(dead_code)]
+    impl StdinPort {
+        fn fill_buffer(&mut self) {
+            if self.buffer.is_empty() {
+                let mut line = String::new();
+                if io::stdin().lock().read_line(&mut line).is_ok() {
+                    self.buffer.extend(line.chars());
+                }
+            }
+        }
+    }
+
+    impl Port for StdinPort {
+        fn is_input(&self) -> bool {
+            true
+        }
+
+        fn read_char(&mut self) -> Option<char> {
+            self.fill_buffer();
+            self.buffer.pop_front()
+        }
+
+        fn peek_char(&mut self) -> Option<char> {
+            self.fill_buffer();
+            self.buffer.front().copied()
+        }
+    }
+
+    /// The port made by open-input-string.
+    #[derive(Debug)]
+    pub struct StringInputPort {
+        chars: VecDeque<char>,
+    }
+
+    #[allow(dead_code)]
+    impl StringInputPort {
+        pub fn new(s: &str) -> Self {
+            StringInputPort {
+                chars: s.chars().collect(),
+            }
+        }
+    }
+
+    impl Port for StringInputPort {
+        fn is_input(&self) -> bool {
+            true
+        }
+
+        fn read_char(&mut self) -> Option<char> {
+            self.chars.pop_front()
+        }
+
+        fn peek_char(&mut self) -> Option<char> {
+            self.chars.front().copied()
+        }
+    }
+
+    /// The port made by open-output-string, which collects what is
+    /// written to it.
+    #[derive(Debug, Default)]
+    pub struct StringOutputPort {
+        buffer: String,
+    }
+
+    impl Port for StringOutputPort {
+        fn is_output(&self) -> bool {
+            true
+        }
+
+        fn write_str(&mut self, s: &str) {
+            self.buffer.push_str(s);
+        }
+
+        fn output_string(&self) -> Option<String> {
+            Some(self.buffer.clone())
+        }
+    }
+
+    /// The port made by open-input-file, read a line at a time.
+    #[derive(Debug)]
+    pub struct FileInputPort {
+        reader: Option<BufReader<File>>,
+        buffer: VecDeque<char>,
+    }
+
+    #[allow(dead_code)]
+    impl FileInputPort {
+        pub fn open(name: &str) -> Self {
+            match File::open(name) {
+                Ok(f) => FileInputPort {
+                    reader: Some(BufReader::new(f)),
+                    buffer: VecDeque::new(),
+                },
+                Err(e) => panic!("cannot open {}: {}", name, e),
+            }
+        }
+
+        fn fill_buffer(&mut self) {
+            let reader = match self.reader.as_mut() {
+                Some(x) => x,
+                None => panic!("port is closed!"),
+            };
+            if self.buffer.is_empty() {
+                let mut line = String::new();
+                if reader.read_line(&mut line).is_ok() {
+                    self.buffer.extend(line.chars());
+                }
+            }
+        }
+    }
+
+    impl Port for FileInputPort {
+        fn is_input(&self) -> bool {
+            true
+        }
+
+        fn read_char(&mut self) -> Option<char> {
+            self.fill_buffer();
+            self.buffer.pop_front()
+        }
+
+        fn peek_char(&mut self) -> Option<char> {
+            self.fill_buffer();
+            self.buffer.front().copied()
+        }
+
+        fn close(&mut self) {
+            self.reader = None;
+            self.buffer.clear();
+        }
+    }
+
+    /// The port made by open-output-file. The file is created, or
+    /// truncated if it exists.
+    #[derive(Debug)]
+    pub struct FileOutputPort {
+        writer: Option<BufWriter<File>>,
+    }
+
+    #[allow(dead_code)]
+    impl FileOutputPort {
+        pub fn create(name: &str) -> Self {
+            match File::create(name) {
+                Ok(f) => FileOutputPort {
+                    writer: Some(BufWriter::new(f)),
+                },
+                Err(e) => panic!("cannot open {}: {}", name, e),
+            }
+        }
+    }
+
+    impl Port for FileOutputPort {
+        fn is_output(&self) -> bool {
+            true
+        }
+
+        fn write_str(&mut self, s: &str) {
+            match self.writer.as_mut() {
+                Some(x) => x.write_all(s.as_bytes()).unwrap(),
+                None => panic!("port is closed!"),
+            }
+        }
+
+        fn flush(&mut self) {
+            if let Some(x) = self.writer.as_mut() {
+                x.flush().unwrap();
+            }
+        }
+
+        fn close(&mut self) {
+            self.flush();
+            self.writer = None;
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn make_port(port: impl Port + 'static) -> Exp {
+        Exp::Port(Rc::new(RefCell::new(port)))
+    }
+
+    /// Opens a file output port. It is remembered until it is dropped, so
+    /// flush_output_ports reaches it when the process ends.
+    #[allow(dead_code)]
+    pub fn open_output_file(name: &str) -> Exp {
+        let port: Rc<RefCell<dyn Port>> = Rc::new(RefCell::new(FileOutputPort::create(name)));
+        OPEN_OUTPUT_PORTS.with(|x| {
+            let mut ports = x.borrow_mut();
+            ports.retain(|x| x.strong_count() > 0);
+            ports.push(Rc::downgrade(&port));
+        });
+        Exp::Port(port)
+    }
+
+    /// Flushes the file output ports still open. The process can end by
+    /// std::process::exit, which runs no destructors, so the driver calls
+    /// this first.
+    #[allow(dead_code)]
+    pub fn flush_output_ports() {
+        OPEN_OUTPUT_PORTS.with(|x| {
+            for port in x.borrow().iter().filter_map(|x| x.upgrade()) {
+                if let Ok(mut port) = port.try_borrow_mut() {
+                    port.flush();
+                }
+            }
+        });
+    }
+
+    fn make_port_parameter(port: impl Port + 'static) -> Rc<Parameter> {
+        Rc::new(Parameter {
+            value: RefCell::new(make_port(port)),
+            converter: None,
+        })
+    }
+
+    // the parameters behind current-input-port, current-output-port and
+    // current-error-port
+    thread_local! {
+        static CURRENT_INPUT_PORT: Rc<Parameter> =
+            make_port_parameter(StdinPort { buffer: VecDeque::new() });
+        static CURRENT_OUTPUT_PORT: Rc<Parameter> = make_port_parameter(StdoutPort);
+        static CURRENT_ERROR_PORT: Rc<Parameter> = make_port_parameter(StderrPort);
+        static OPEN_OUTPUT_PORTS: RefCell<Vec<Weak<RefCell<dyn Port>>>> =
+            const { RefCell::new(vec![]) };
+    }
+
+    #[allow(dead_code)]
+    pub fn current_input_port() -> Rc<Parameter> {
+        CURRENT_INPUT_PORT.with(|x| x.clone())
+    }
+
+    #[allow(dead_code)]
+    pub fn current_output_port() -> Rc<Parameter> {
+        CURRENT_OUTPUT_PORT.with(|x| x.clone())
+    }
+
+    #[allow(dead_code)]
+    pub fn current_error_port() -> Rc<Parameter> {
+        CURRENT_ERROR_PORT.with(|x| x.clone())
+    }
+
+    #[allow(dead_code)]
+    pub fn set_current_output_port(port: impl Port + 'static) {
+        current_output_port().value.replace(make_port(port));
+    }
+
+    /// The port argument of an output procedure, or the current output
+    /// port when it is left out.
+    #[allow(dead_code)]
+    pub fn output_port(port: Option<Exp>) -> Rc<RefCell<dyn Port>> {
+        let port = port.unwrap_or_else(|| current_output_port().value.borrow().clone());
+        match port {
+            Exp::Port(x) if x.borrow().is_output() => x,
+            _ => panic!("not an output port!"),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn input_port(port: Option<Exp>) -> Rc<RefCell<dyn Port>> {
+        let port = port.unwrap_or_else(|| current_input_port().value.borrow().clone());
+        match port {
+            Exp::Port(x) if x.borrow().is_input() => x,
+            _ => panic!("not an input port!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::port::{output_port, set_current_output_port};
+    use crate::core_of_interpreter::test::Recorder;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_redirect_current_output_port() {
+        let output = Rc::new(RefCell::new(String::new()));
+        set_current_output_port(Recorder(output.clone()));
+        output_port(None).borrow_mut().write_str("hello");
+        output_port(None).borrow_mut().write_str(" world");
+        assert_eq!(*output.borrow(), "hello world");
+    }
+}
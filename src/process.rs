@@ -0,0 +1,133 @@
+#[allow(clippy::module_inception)]
+pub mod process {
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use crate::tool::tools::{scheme_cons, vec_to_list};
+    use std::env;
+    use std::io::{self, Write};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::OnceLock;
+
+    static COMMAND_LINE: OnceLock<Vec<String>> = OnceLock::new();
+
+    /// Raised by exit. It unwinds to the driver, so parameterize and
+    /// library guards are dropped on the way. The driver then flushes the
+    /// file ports still open and ends the process with the code.
+    #[derive(Debug)]
+    pub struct Exit(pub i32);
+
+    /// Sets what command-line returns, it can only be set once.
+    #[allow(dead_code)]
+    pub fn set_command_line(args: Vec<String>) {
+        COMMAND_LINE.set(args).ok();
+    }
+
+    #[allow(dead_code)]
+    pub fn command_line() -> Exp {
+        let args = COMMAND_LINE.get_or_init(|| env::args().collect());
+        let args: Vec<Exp> = args.iter().map(|x| Exp::SchemeString(x.clone())).collect();
+        vec_to_list(&args)
+    }
+
+    // #t or no object is success, #f is failure. An exit status is a
+    // byte, so an integer out of 0..=255 is failure rather than being
+    // cut down to its low byte, which could make it 0.
+    fn exit_code(obj: Option<Exp>) -> i32 {
+        match obj {
+            None | Some(Exp::Bool(true)) => 0,
+            Some(Exp::Bool(false)) => 1,
+            Some(Exp::Integer(n)) if (0..=255).contains(&n) => n as i32,
+            Some(Exp::Integer(_)) => 1,
+            _ => panic!("wrong type for exit!"),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn exit(obj: Option<Exp>) -> ! {
+        panic::panic_any(Exit(exit_code(obj)))
+    }
+
+    /// Ends the process at once, without unwinding.
+    #[allow(dead_code)]
+    pub fn emergency_exit(obj: Option<Exp>) -> ! {
+        let code = exit_code(obj);
+        io::stdout().flush().ok();
+        std::process::exit(code)
+    }
+
+    /// Keeps the panic hook quiet about exit, which is not an error.
+    #[allow(dead_code)]
+    pub fn install_exit_hook() {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.payload().downcast_ref::<Exit>().is_none() {
+                hook(info);
+            }
+        }));
+    }
+
+    /// Runs f, returning the code passed to exit, or 0 when f returns.
+    /// Any other panic goes on unwinding.
+    #[allow(dead_code)]
+    pub fn run_until_exit<F: FnOnce()>(f: F) -> i32 {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(()) => 0,
+            Err(payload) => match payload.downcast::<Exit>() {
+                Ok(exit) => exit.0,
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get_environment_variable(name: Exp) -> Exp {
+        match name {
+            Exp::SchemeString(x) => match env::var_os(x) {
+                Some(value) => Exp::SchemeString(value.to_string_lossy().into_owned()),
+                None => Exp::Bool(false),
+            },
+            _ => panic!("wrong type for get-environment-variable!"),
+        }
+    }
+
+    /// All environment variables as a list of entries. There are no
+    /// dotted pairs, so cons makes each entry the two-element list
+    /// (name value) and the value is its cadr, not its cdr.
+    #[allow(dead_code)]
+    pub fn get_environment_variables() -> Exp {
+        let bindings: Vec<Exp> = env::vars_os()
+            .map(|(name, value)| {
+                scheme_cons(
+                    Exp::SchemeString(name.to_string_lossy().into_owned()),
+                    Exp::SchemeString(value.to_string_lossy().into_owned()),
+                )
+            })
+            .collect();
+        vec_to_list(&bindings)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::process::{exit, get_environment_variable, run_until_exit};
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+
+    #[test]
+    fn test_run_until_exit() {
+        assert_eq!(run_until_exit(|| {}), 0);
+        assert_eq!(run_until_exit(|| exit(Some(Exp::Integer(3)))), 3);
+        assert_eq!(run_until_exit(|| exit(Some(Exp::Bool(false)))), 1);
+        assert_eq!(run_until_exit(|| exit(None)), 0);
+        assert_eq!(run_until_exit(|| exit(Some(Exp::Integer(256)))), 1);
+        assert_eq!(run_until_exit(|| exit(Some(Exp::Integer(-1)))), 1);
+    }
+
+    #[test]
+    fn test_get_environment_variable() {
+        assert_eq!(
+            get_environment_variable(Exp::SchemeString(
+                "RUSCHEME_SURELY_UNSET_VARIABLE".to_string()
+            )),
+            Exp::Bool(false)
+        );
+    }
+}
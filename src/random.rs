@@ -0,0 +1,151 @@
+#[allow(clippy::module_inception)]
+pub mod random {
+    use crate::bigint::bigint::BigInt;
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use crate::number::number::from_big_integer;
+    use std::cell::RefCell;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /* xoshiro256**, a small generator of good statistical quality, it is
+     * not fit for cryptography. The state is seeded by splitmix64, from
+     * the clock unless random-seed! has been called. */
+    thread_local! {
+        static STATE: RefCell<Option<[u64; 4]>> = const { RefCell::new(None) };
+    }
+
+    fn splitmix64(x: &mut u64) -> u64 {
+        *x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *x;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn seeded_state(seed: u64) -> [u64; 4] {
+        let mut x = seed;
+        [
+            splitmix64(&mut x),
+            splitmix64(&mut x),
+            splitmix64(&mut x),
+            splitmix64(&mut x),
+        ]
+    }
+
+    /// Restarts the generator, so the same seed gives the same numbers.
+    #[allow(dead_code)]
+    pub fn set_seed(seed: u64) {
+        STATE.with(|x| x.replace(Some(seeded_state(seed))));
+    }
+
+    #[allow(dead_code)]
+    pub fn next_u64() -> u64 {
+        STATE.with(|x| {
+            let mut state = x.borrow_mut();
+            let s = state.get_or_insert_with(|| {
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64);
+                seeded_state(nanos)
+            });
+            let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+            let t = s[1] << 17;
+            s[2] ^= s[0];
+            s[3] ^= s[1];
+            s[1] ^= s[2];
+            s[0] ^= s[3];
+            s[2] ^= t;
+            s[3] = s[3].rotate_left(45);
+            result
+        })
+    }
+
+    // uniform in [0, n), rejecting the values that would make the
+    // low end more likely
+    fn below(n: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    // uniform in [0, n) for n of any size, drawn bit by bit
+    fn big_below(n: &BigInt) -> BigInt {
+        let bits = n.integer_length();
+        loop {
+            let mut x = BigInt::from_i128(0);
+            let mut drawn = 0;
+            while drawn < bits {
+                let k = (bits - drawn).min(64);
+                let chunk = next_u64() >> (64 - k);
+                x = x.shift(k as i64).add(&BigInt::from_i128(chunk as i128));
+                drawn += k;
+            }
+            if x < *n {
+                return x;
+            }
+        }
+    }
+
+    /// (random n) is an exact integer below an exact n, or a float
+    /// below an inexact one.
+    #[allow(dead_code)]
+    pub fn random(limit: Exp) -> Exp {
+        match limit {
+            Exp::Integer(n) if n > 0 => Exp::Integer(below(n as u64) as i64),
+            Exp::BigInteger(ref n) if !n.is_negative() => from_big_integer(big_below(n)),
+            // 53 random bits make a float in [0, 1)
+            Exp::FloatNumber(f) if f > 0.0 => {
+                Exp::FloatNumber((next_u64() >> 11) as f64 / (1u64 << 53) as f64 * f)
+            }
+            _ => panic!("wrong type for random!"),
+        }
+    }
+
+    /// (random-seed! s) takes any exact integer, only the low 64 bits of
+    /// a big one count.
+    #[allow(dead_code)]
+    pub fn random_seed(seed: Exp) {
+        match seed {
+            Exp::Integer(n) => set_seed(n as u64),
+            Exp::BigInteger(n) => set_seed(n.low_u64()),
+            _ => panic!("wrong type for random-seed!!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::random::{next_u64, random, random_seed, set_seed};
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+
+    #[test]
+    fn test_seed_is_reproducible() {
+        set_seed(42);
+        let first: Vec<u64> = (0..4).map(|_| next_u64()).collect();
+        set_seed(42);
+        let second: Vec<u64> = (0..4).map(|_| next_u64()).collect();
+        assert_eq!(first, second);
+        random_seed(Exp::Integer(7));
+        let x = random(Exp::Integer(1000));
+        set_seed(7);
+        assert_eq!(random(Exp::Integer(1000)), x);
+    }
+
+    #[test]
+    fn test_random_range() {
+        set_seed(1);
+        for _ in 0..1000 {
+            match random(Exp::Integer(6)) {
+                Exp::Integer(x) => assert!((0..6).contains(&x)),
+                _ => panic!("not an integer!"),
+            }
+            match random(Exp::FloatNumber(0.5)) {
+                Exp::FloatNumber(x) => assert!((0.0..0.5).contains(&x)),
+                _ => panic!("not a float!"),
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+#[allow(clippy::module_inception)]
+pub mod record {
+    use crate::core_of_interpreter::core_of_interpreter::Exp;
+    use crate::display::display::exp_to_string;
+    use crate::represent::represent::{car, cdr};
+    use crate::symbol::symbol::Symbol;
+    use crate::tool::tools::list_length;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A type created by define-record-type.
+    #[derive(Debug)]
+    pub struct RecordType {
+        pub name: Symbol,
+        pub fields: Vec<Symbol>,
+    }
+
+    /// An instance of a record type. Fields are mutable through the
+    /// modifiers of its type.
+    #[derive(Debug)]
+    pub struct Record {
+        pub record_type: Rc<RecordType>,
+        pub values: RefCell<Vec<Exp>>,
+    }
+
+    /// The procedures generated by define-record-type. Fields are kept
+    /// as indexes into the values of a record.
+    #[derive(Debug)]
+    pub enum RecordProcedure {
+        Constructor(Rc<RecordType>, Vec<usize>),
+        Predicate(Rc<RecordType>),
+        Accessor(Rc<RecordType>, usize),
+        Modifier(Rc<RecordType>, usize),
+    }
+
+    #[allow(dead_code)]
+    impl RecordType {
+        // the index of a field, which must have been declared
+        pub fn field_index(&self, field: Symbol) -> usize {
+            match self.fields.iter().position(|x| *x == field) {
+                Some(i) => i,
+                None => panic!("unknow record field: {}", field),
+            }
+        }
+
+        // <point> is shown as point
+        pub fn display_name(&self) -> &'static str {
+            let name = self.name.as_str();
+            if name.len() > 2 && name.starts_with('<') && name.ends_with('>') {
+                &name[1..name.len() - 1]
+            } else {
+                name
+            }
+        }
+    }
+
+    // the record in args, which must be of type t
+    fn record_argument(t: &Rc<RecordType>, args: Exp) -> Rc<Record> {
+        match car(args) {
+            Ok(Exp::Record(x)) if Rc::ptr_eq(&x.record_type, t) => x,
+            _ => panic!("not a record of type {}!", t.display_name()),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn apply_record_procedure(p: &RecordProcedure, args: Exp) -> Exp {
+        match p {
+            RecordProcedure::Constructor(t, indexes) => {
+                if list_length(args.clone()) != indexes.len() as i32 {
+                    panic!("wrong number of args!");
+                }
+                let mut values = vec![Exp::Bool(false); t.fields.len()];
+                let mut rest = args;
+                for i in indexes.iter() {
+                    values[*i] = car(rest.clone()).unwrap();
+                    rest = cdr(rest).unwrap();
+                }
+                Exp::Record(Rc::new(Record {
+                    record_type: t.clone(),
+                    values: RefCell::new(values),
+                }))
+            }
+            RecordProcedure::Predicate(t) => match car(args) {
+                Ok(Exp::Record(x)) => Exp::Bool(Rc::ptr_eq(&x.record_type, t)),
+                _ => Exp::Bool(false),
+            },
+            RecordProcedure::Accessor(t, i) => {
+                let record = record_argument(t, args);
+                let value = record.values.borrow()[*i].clone();
+                value
+            }
+            RecordProcedure::Modifier(t, i) => {
+                let record = record_argument(t, args.clone());
+                let value = car(cdr(args).unwrap()).unwrap();
+                record.values.borrow_mut()[*i] = value;
+                Exp::Unspecified
+            }
+        }
+    }
+
+    /// #<point x=1 y=2>
+    #[allow(dead_code)]
+    pub fn record_to_string(record: &Record, write: bool) -> String {
+        let t = &record.record_type;
+        let mut s = format!("#<{}", t.display_name());
+        for (field, value) in t.fields.iter().zip(record.values.borrow().iter()) {
+            s.push_str(&format!(" {}={}", field, exp_to_string(value, write)));
+        }
+        s.push('>');
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::record::{apply_record_procedure, RecordProcedure, RecordType};
+    use crate::core_of_interpreter::core_of_interpreter::{Exp, Pair};
+    use crate::scheme_list;
+    use crate::symbol::symbol::Symbol;
+    use crate::tool::tools::{append, scheme_cons};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_record_procedures() {
+        let point = Rc::new(RecordType {
+            name: Symbol::new("<point>"),
+            fields: vec![Symbol::new("x"), Symbol::new("y")],
+        });
+        assert_eq!(point.display_name(), "point");
+        assert_eq!(point.field_index(Symbol::new("y")), 1);
+        // (make-point y x)
+        let constructor = RecordProcedure::Constructor(point.clone(), vec![1, 0]);
+        let p =
+            apply_record_procedure(&constructor, scheme_list!(Exp::Integer(2), Exp::Integer(1)));
+        let is_point = RecordProcedure::Predicate(point.clone());
+        let point_x = RecordProcedure::Accessor(point.clone(), 0);
+        let set_point_x = RecordProcedure::Modifier(point, 0);
+        assert_eq!(
+            apply_record_procedure(&is_point, scheme_list!(p.clone())),
+            Exp::Bool(true)
+        );
+        assert_eq!(
+            apply_record_procedure(&is_point, scheme_list!(Exp::Integer(1))),
+            Exp::Bool(false)
+        );
+        assert_eq!(
+            apply_record_procedure(&point_x, scheme_list!(p.clone())),
+            Exp::Integer(1)
+        );
+        apply_record_procedure(&set_point_x, scheme_list!(p.clone(), Exp::Integer(5)));
+        assert_eq!(
+            apply_record_procedure(&point_x, scheme_list!(p)),
+            Exp::Integer(5)
+        );
+    }
+}
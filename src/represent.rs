@@ -1,13 +1,16 @@
 #![allow(unused_variables)]
+#[allow(clippy::module_inception)]
 pub mod represent {
-    use crate::tool::tools::{append, scheme_cons};
-    use crate::{
-        core_of_interpreter::core_of_interpreter::{Exp, Pair},
-        scheme_list,
+    use crate::core_of_interpreter::core_of_interpreter::{Exp, Pair};
+    use crate::symbol::symbol::{
+        Symbol, BEGIN, DEFINE, DEFINE_LIBRARY, DEFINE_RECORD_TYPE, IF, IMPORT, INCLUDE, LAMBDA,
+        PARAMETERIZE, QUOTE, SET, THE_ENVIRONMENT, TIME,
     };
+    use crate::tool::tools::scheme_cons;
+    use std::rc::Rc;
 
     /* operatons on Exp as enum methods */
-    #[allow(dead_code)]
+    #[allow(dead_code, clippy::match_like_matches_macro)]
     impl Exp {
         pub fn is_pair(&self) -> bool {
             match self {
@@ -19,38 +22,12 @@ pub mod represent {
             }
         }
 
-        pub fn is_primitive_procedure(&self) -> bool {
-            if let Exp::Symbol(x) = self {
-                match x {
-                    t if *t == "*".to_string() => true,
-                    t if *t == "/".to_string() => true,
-                    t if *t == "+".to_string() => true,
-                    t if *t == "-".to_string() => true,
-                    t if *t == "car".to_string() => true,
-                    t if *t == "cdr".to_string() => true,
-                    t if *t == "cons".to_string() => true,
-                    t if *t == "null?".to_string() => true,
-                    t if *t == "=".to_string() => true,
-                    t if *t == ">".to_string() => true,
-                    t if *t == "<".to_string() => true,
-                    t if *t == "display".to_string() => true,
-                    t if *t == "eq?".to_string() => true,
-                    _ => false,
-                }
-            } else {
-                false
-            }
-        }
-
         pub fn is_variable(&self) -> bool {
             self.is_symbol()
         }
 
         pub fn is_quoted(&self) -> bool {
-            match self {
-                Exp::Quote(_x) => true,
-                _ => false,
-            }
+            is_tagged_list(self.clone(), QUOTE)
         }
 
         pub fn is_string(&self) -> bool {
@@ -71,28 +48,53 @@ pub mod represent {
             match self {
                 Exp::FloatNumber(_x) => true,
                 Exp::Integer(_x) => true,
+                Exp::BigInteger(_x) => true,
+                Exp::Rational(_x, _y) => true,
+                Exp::BigRational(_x, _y) => true,
+                Exp::Complex(_x, _y) => true,
                 _ => false,
             }
         }
 
-        pub fn to_f32(&self) -> f32 {
+        pub fn to_f64(&self) -> f64 {
             match self {
                 Exp::FloatNumber(x) => *x,
                 _ => {
-                    panic!("not a f32 !");
+                    panic!("not a f64 !");
                 }
             }
         }
 
-        pub fn to_i32(&self) -> i32 {
+        pub fn to_i64(&self) -> i64 {
             match self {
                 Exp::Integer(x) => *x,
                 _ => {
-                    panic!("not a i32 !");
+                    panic!("not a i64 !");
                 }
             }
         }
 
+        pub fn is_integer(&self) -> bool {
+            match self {
+                Exp::Integer(x) => true,
+                Exp::BigInteger(x) => true,
+                _ => false,
+            }
+        }
+
+        /// a proper list, which is either () or a pair.
+        pub fn is_list(&self) -> bool {
+            match self {
+                Exp::List(x) => true,
+                _ => false,
+            }
+        }
+
+        /// Everything but false counts as true in a condition.
+        pub fn is_true(&self) -> bool {
+            *self != Exp::Bool(false)
+        }
+
         pub fn is_bool(&self) -> bool {
             match self {
                 Exp::Bool(x) => true,
@@ -100,17 +102,118 @@ pub mod represent {
             }
         }
 
+        pub fn is_char(&self) -> bool {
+            match self {
+                Exp::Character(x) => true,
+                _ => false,
+            }
+        }
+
+        pub fn is_vector(&self) -> bool {
+            match self {
+                Exp::Vector(x) => true,
+                _ => false,
+            }
+        }
+
+        pub fn is_bytevector(&self) -> bool {
+            match self {
+                Exp::Bytevector(x) => true,
+                _ => false,
+            }
+        }
+
+        pub fn is_port(&self) -> bool {
+            match self {
+                Exp::Port(x) => true,
+                _ => false,
+            }
+        }
+
         pub fn is_self_evaluating(&self) -> bool {
-            self.is_null()
+            self.is_vector()
+                || self.is_bytevector()
+                || self.is_null()
                 || self.is_number()
                 || self.is_string()
                 || self.is_bool()
+                || self.is_char()
+                || *self == Exp::Unspecified
+                || *self == Exp::Eof
                 || is_number_combination(self.clone())
         }
 
         pub fn is_null(&self) -> bool {
             *self == Exp::List(Pair::Nil)
         }
+
+        /// eq? tells whether two objects are the same object, so two
+        /// pairs are eq? only when one is a copy of the other.
+        pub fn is_eq(&self, other: &Exp) -> bool {
+            match (self, other) {
+                (Exp::List(Pair::Nil), Exp::List(Pair::Nil)) => true,
+                (Exp::List(Pair::Cons(a, b)), Exp::List(Pair::Cons(c, d))) => {
+                    Rc::ptr_eq(a, c) && Rc::ptr_eq(b, d)
+                }
+                (Exp::Integer(x), Exp::Integer(y)) => x == y,
+                (Exp::Symbol(x), Exp::Symbol(y)) => x == y,
+                (Exp::Bool(x), Exp::Bool(y)) => x == y,
+                (Exp::Character(x), Exp::Character(y)) => x == y,
+                (Exp::Primitive(x), Exp::Primitive(y)) => x == y,
+                (Exp::Procedure(x), Exp::Procedure(y)) => Rc::ptr_eq(x, y),
+                (Exp::Vector(x), Exp::Vector(y)) => Rc::ptr_eq(x, y),
+                (Exp::Record(x), Exp::Record(y)) => Rc::ptr_eq(x, y),
+                (Exp::RecordType(x), Exp::RecordType(y)) => Rc::ptr_eq(x, y),
+                (Exp::RecordProcedure(x), Exp::RecordProcedure(y)) => Rc::ptr_eq(x, y),
+                (Exp::Parameter(x), Exp::Parameter(y)) => Rc::ptr_eq(x, y),
+                (Exp::Bytevector(x), Exp::Bytevector(y)) => Rc::ptr_eq(x, y),
+                (Exp::Port(x), Exp::Port(y)) => Rc::ptr_eq(x, y),
+                (Exp::Eof, Exp::Eof) => true,
+                (Exp::Unspecified, Exp::Unspecified) => true,
+                (Exp::Environment(x), Exp::Environment(y)) => x == y,
+                // a string has no identity to compare, see the README
+                (Exp::SchemeString(x), Exp::SchemeString(y)) => x == y,
+                _ => false,
+            }
+        }
+
+        /// eqv? is eq? extended to numbers of the same exactness and value.
+        pub fn is_eqv(&self, other: &Exp) -> bool {
+            match (self, other) {
+                (Exp::FloatNumber(x), Exp::FloatNumber(y)) => {
+                    x.to_bits() == y.to_bits() || (x.is_nan() && y.is_nan())
+                }
+                (Exp::BigInteger(x), Exp::BigInteger(y)) => x == y,
+                (Exp::Rational(x, y), Exp::Rational(x1, y1)) => x == x1 && y == y1,
+                (Exp::BigRational(x, y), Exp::BigRational(x1, y1)) => x == x1 && y == y1,
+                (Exp::Complex(x, y), Exp::Complex(x1, y1)) => x.is_eqv(x1) && y.is_eqv(y1),
+                _ => self.is_eq(other),
+            }
+        }
+
+        /// equal? compares lists, vectors and bytevectors element by element
+        /// with eqv?.
+        pub fn is_equal(&self, other: &Exp) -> bool {
+            let mut lhs = self.clone();
+            let mut rhs = other.clone();
+            while lhs.is_pair() && rhs.is_pair() {
+                if !car(lhs.clone())
+                    .unwrap()
+                    .is_equal(&car(rhs.clone()).unwrap())
+                {
+                    return false;
+                }
+                lhs = cdr(lhs).unwrap();
+                rhs = cdr(rhs).unwrap();
+            }
+            match (lhs, rhs) {
+                (Exp::Vector(x), Exp::Vector(y)) => {
+                    x.len() == y.len() && x.iter().zip(y.iter()).all(|(x, y)| x.is_equal(y))
+                }
+                (Exp::Bytevector(x), Exp::Bytevector(y)) => x == y,
+                (lhs, rhs) => lhs.is_eqv(&rhs),
+            }
+        }
     }
 
     /* operations on Exp as function */
@@ -119,17 +222,33 @@ pub mod represent {
         match exp {
             Exp::FloatNumber(x) => true,
             Exp::Integer(x) => true,
+            Exp::BigInteger(x) => true,
+            Exp::Rational(x, y) => true,
+            Exp::BigRational(x, y) => true,
+            Exp::Complex(x, y) => true,
             Exp::Symbol(x) => false,
-            Exp::Quote(x) => false,
             Exp::SchemeString(x) => false,
             Exp::Bool(x) => false,
+            Exp::Character(x) => false,
+            Exp::Primitive(x) => false,
+            Exp::Procedure(x) => false,
+            Exp::Vector(x) => false,
+            Exp::Record(x) => false,
+            Exp::RecordType(x) => false,
+            Exp::RecordProcedure(x) => false,
+            Exp::Parameter(x) => false,
+            Exp::Bytevector(x) => false,
+            Exp::Port(x) => false,
+            Exp::Eof => false,
+            Exp::Unspecified => false,
+            Exp::Environment(x) => false,
             Exp::List(Pair::Nil) => false,
             Exp::List(Pair::Cons(x, y)) => {
-                let s = is_number_combination(*x);
+                let s = is_number_combination((*x).clone());
                 if s {
                     let mut temp = y;
-                    while let Pair::Cons(lhs, rhs) = *temp {
-                        let s1 = is_number_combination(*lhs);
+                    while let Pair::Cons(lhs, rhs) = (*temp).clone() {
+                        let s1 = is_number_combination((*lhs).clone());
                         if s1 {
                             if *rhs == Pair::Nil {
                                 break;
@@ -140,17 +259,23 @@ pub mod represent {
                             return false;
                         }
                     }
-                    return true;
+                    true
                 } else {
-                    return false;
+                    false
                 }
             }
         }
     }
 
+    // (quote x) => x
+    #[allow(dead_code)]
+    pub fn text_of_quotation(exp: Exp) -> Exp {
+        cadr(exp).unwrap()
+    }
+
     #[allow(dead_code)]
     pub fn is_assignment(exp: Exp) -> bool {
-        is_tagged_list(exp, "set!")
+        is_tagged_list(exp, SET)
     }
 
     #[allow(dead_code)]
@@ -165,7 +290,7 @@ pub mod represent {
     // definiton
     #[allow(dead_code)]
     pub fn is_definiton(exp: Exp) -> bool {
-        is_tagged_list(exp, "define")
+        is_tagged_list(exp, DEFINE)
     }
 
     #[allow(dead_code)]
@@ -192,10 +317,92 @@ pub mod represent {
         }
     }
 
+    // (define-record-type point (make-point x y) point? (x point-x set-point-x!))
+    #[allow(dead_code)]
+    pub fn is_record_type_definition(exp: Exp) -> bool {
+        is_tagged_list(exp, DEFINE_RECORD_TYPE)
+    }
+
+    #[allow(dead_code)]
+    pub fn record_type_name(exp: Exp) -> Exp {
+        cadr(exp).unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn record_constructor(exp: Exp) -> Exp {
+        caddr(exp).unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn record_predicate(exp: Exp) -> Exp {
+        cadddr(exp).unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn record_fields(exp: Exp) -> Exp {
+        cdr(cdddr(exp).unwrap()).unwrap()
+    }
+
+    // (parameterize ((p 1) (q 2)) body)
+    #[allow(dead_code)]
+    pub fn is_parameterize(exp: Exp) -> bool {
+        is_tagged_list(exp, PARAMETERIZE)
+    }
+
+    #[allow(dead_code)]
+    pub fn parameterize_bindings(exp: Exp) -> Exp {
+        cadr(exp).unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn parameterize_body(exp: Exp) -> Exp {
+        cddr(exp).unwrap()
+    }
+
+    // (include "file" ...)
+    #[allow(dead_code)]
+    pub fn is_include(exp: Exp) -> bool {
+        is_tagged_list(exp, INCLUDE)
+    }
+
+    #[allow(dead_code)]
+    pub fn include_files(exp: Exp) -> Exp {
+        cdr(exp).unwrap()
+    }
+
+    // (define-library name declaration ...)
+    #[allow(dead_code)]
+    pub fn is_library_definition(exp: Exp) -> bool {
+        is_tagged_list(exp, DEFINE_LIBRARY)
+    }
+
+    // (import import-set ...)
+    #[allow(dead_code)]
+    pub fn is_import(exp: Exp) -> bool {
+        is_tagged_list(exp, IMPORT)
+    }
+
+    // (the-environment)
+    #[allow(dead_code)]
+    pub fn is_the_environment(exp: Exp) -> bool {
+        is_tagged_list(exp, THE_ENVIRONMENT)
+    }
+
+    // (time expr)
+    #[allow(dead_code)]
+    pub fn is_time(exp: Exp) -> bool {
+        is_tagged_list(exp, TIME)
+    }
+
+    #[allow(dead_code)]
+    pub fn time_expression(exp: Exp) -> Exp {
+        cadr(exp).unwrap()
+    }
+
     // lambda
     #[allow(dead_code)]
     pub fn is_lambda(exp: Exp) -> bool {
-        is_tagged_list(exp, "lambda")
+        is_tagged_list(exp, LAMBDA)
     }
 
     #[allow(dead_code)]
@@ -210,16 +417,13 @@ pub mod represent {
 
     #[allow(dead_code)]
     pub fn make_lambda(parameters: Exp, body: Exp) -> Exp {
-        scheme_cons(
-            Exp::Symbol("lambda".to_string()),
-            scheme_cons(parameters, body),
-        )
+        scheme_cons(Exp::Symbol(LAMBDA), scheme_cons(parameters, body))
     }
 
     // if
     #[allow(dead_code)]
     pub fn is_if(exp: Exp) -> bool {
-        is_tagged_list(exp, "if")
+        is_tagged_list(exp, IF)
     }
 
     #[allow(dead_code)]
@@ -238,13 +442,13 @@ pub mod represent {
         if cdddr(exp.clone()).unwrap() != s {
             cadddr(exp.clone()).unwrap()
         } else {
-            panic!("if alternative not exist!");
+            Exp::Unspecified
         }
     }
 
     #[allow(dead_code)]
     pub fn make_if(predicate: Exp, consequent: Exp, alternative: Exp) -> Exp {
-        let tag = Exp::Symbol("if".to_string());
+        let tag = Exp::Symbol(IF);
         let null = Exp::List(Pair::Nil);
         scheme_cons(
             tag,
@@ -258,7 +462,7 @@ pub mod represent {
     // begin
     #[allow(dead_code)]
     pub fn is_begin(exp: Exp) -> bool {
-        is_tagged_list(exp, "begin")
+        is_tagged_list(exp, BEGIN)
     }
 
     #[allow(dead_code)]
@@ -296,33 +500,75 @@ pub mod represent {
 
     #[allow(dead_code)]
     pub fn make_begin(seq: Exp) -> Exp {
-        scheme_cons(Exp::Symbol("begin".to_string()), seq)
+        scheme_cons(Exp::Symbol(BEGIN), seq)
+    }
+
+    /// A compound procedure made by lambda. It is a variant of its own,
+    /// so no quoted list can pass for one.
+    #[derive(Debug)]
+    pub struct Procedure {
+        parameters: Exp,
+        body: Exp,
+        library: Option<Symbol>,
     }
 
     // representing procedures
     #[allow(dead_code)]
     pub fn make_procedure(parameters: Exp, body: Exp) -> Exp {
-        scheme_list!(Exp::Symbol("procedure".to_string()), parameters, body)
+        Exp::Procedure(Rc::new(Procedure {
+            parameters,
+            body,
+            library: None,
+        }))
     }
 
+    // a procedure made inside a library remembers it, to run in the
+    // global environment of the library
     #[allow(dead_code)]
-    pub fn is_compound_procedure(p: Exp) -> bool {
-        is_tagged_list(p, "procedure")
+    pub fn make_library_procedure(parameters: Exp, body: Exp, library: Symbol) -> Exp {
+        Exp::Procedure(Rc::new(Procedure {
+            parameters,
+            body,
+            library: Some(library),
+        }))
+    }
+
+    fn compound_procedure(p: &Exp) -> &Procedure {
+        match p {
+            Exp::Procedure(x) => x,
+            _ => panic!("not a compound procedure!"),
+        }
     }
 
     #[allow(dead_code)]
+    pub fn procedure_library(p: Exp) -> Option<Symbol> {
+        compound_procedure(&p).library
+    }
+
+    #[allow(dead_code, clippy::match_like_matches_macro)]
+    pub fn is_compound_procedure(p: Exp) -> bool {
+        match p {
+            Exp::Procedure(_x) => true,
+            _ => false,
+        }
+    }
+
+    #[allow(dead_code, clippy::match_like_matches_macro)]
     pub fn is_primitive_procedure(p: Exp) -> bool {
-        is_tagged_list(p, "primitive")
+        match p {
+            Exp::Primitive(_x) => true,
+            _ => false,
+        }
     }
 
     #[allow(dead_code)]
     pub fn procedure_parameters(p: Exp) -> Exp {
-        cadr(p).unwrap()
+        compound_procedure(&p).parameters.clone()
     }
 
     #[allow(dead_code)]
     pub fn procedure_body(p: Exp) -> Exp {
-        caddr(p).unwrap()
+        compound_procedure(&p).body.clone()
     }
 
     /*
@@ -383,7 +629,7 @@ pub mod represent {
             Exp::List(_x) => {
                 if exp.is_pair() {
                     if let Exp::List(Pair::Cons(x, _y)) = exp {
-                        Ok(*x.clone())
+                        Ok((*x).clone())
                     } else {
                         Err("error happens!")
                     }
@@ -408,7 +654,7 @@ pub mod represent {
             Exp::List(_x) => {
                 if exp.is_pair() {
                     if let Exp::List(Pair::Cons(_x, y)) = exp {
-                        let z = Exp::List(*y);
+                        let z = Exp::List((*y).clone());
                         Ok(z)
                     } else {
                         Err("error happens!")
@@ -427,8 +673,8 @@ pub mod represent {
             Exp::List(_x) => {
                 if exp.is_pair() {
                     if let Exp::List(Pair::Cons(_x, y)) = exp {
-                        if let Pair::Cons(a, _b) = *y {
-                            Ok(*a.clone())
+                        if let Pair::Cons(a, _b) = &*y {
+                            Ok((**a).clone())
                         } else {
                             Err("error happens!")
                         }
@@ -491,18 +737,14 @@ pub mod represent {
     #[allow(dead_code)]
     pub fn cdadr(exp: Exp) -> Result<Exp, &'static str> {
         let s1 = cadr(exp).unwrap();
-        let s2 = cdr(s1);
-        s2
+        cdr(s1)
     }
 
     #[allow(dead_code)]
-    pub fn is_tagged_list(exp: Exp, tag: &'static str) -> bool {
+    pub fn is_tagged_list(exp: Exp, tag: Symbol) -> bool {
         if exp.is_pair() {
             if let Ok(Exp::Symbol(x)) = car(exp) {
-                match x {
-                    t if t == tag => true,
-                    _ => false,
-                }
+                x == tag
             } else {
                 false
             }
@@ -512,11 +754,14 @@ pub mod represent {
     }
 }
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::represent::*;
-    use crate::core_of_interpreter::core_of_interpreter::{Env, Exp, Pair};
+    use crate::core_of_interpreter::core_of_interpreter::{Exp, Pair};
+    use crate::scheme_list;
+    use crate::symbol::symbol::{Symbol, QUOTE};
     use crate::tool::tools::{append, generate_test_data, scheme_cons};
-    use crate::{scheme_list, tool::tools::generate_test_frames};
+    use std::rc::Rc;
     #[test]
     fn test_is_number() {
         let x = Exp::Integer(3);
@@ -526,11 +771,12 @@ mod tests {
     #[test]
     fn test_is_string() {
         let str = "summer";
-        let x = Exp::Symbol(str.to_string());
+        let x = Exp::Symbol(Symbol::new(str));
         assert_eq!(x.is_symbol(), true);
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_is_self_evaluating() {
         let x = Exp::FloatNumber(3.14);
         let y = Exp::SchemeString("Winter".to_string());
@@ -539,26 +785,26 @@ mod tests {
 
     #[test]
     fn test_is_symbol() {
-        let x = Exp::Symbol("item".to_string());
+        let x = Exp::Symbol(Symbol::new("item"));
         assert_eq!(x.is_symbol(), true);
     }
 
     #[test]
     fn test_is_pair() {
-        let a = Box::new(Exp::Integer(1));
-        let b = Box::new(Exp::Integer(2));
-        let c = Box::new(Exp::Integer(3));
-        let d = Box::new(Pair::Nil);
+        let a = Rc::new(Exp::Integer(1));
+        let b = Rc::new(Exp::Integer(2));
+        let c = Rc::new(Exp::Integer(3));
+        let d = Rc::new(Pair::Nil);
         let x = Pair::Cons(c, d);
-        let y = Pair::Cons(b, Box::new(x));
-        let z = Pair::Cons(a, Box::new(y));
+        let y = Pair::Cons(b, Rc::new(x));
+        let z = Pair::Cons(a, Rc::new(y));
         let s = Exp::List(z);
         assert_eq!(s.is_pair(), true);
     }
 
     #[test]
     fn test_is_quoted() {
-        let x = Exp::Quote("'x".to_string());
+        let x = scheme_list!(Exp::Symbol(QUOTE), Exp::Symbol(Symbol::new("x")));
         assert_eq!(x.is_quoted(), true);
     }
 
@@ -566,37 +812,37 @@ mod tests {
     fn test_list_operatioins() {
         // It's painful to build List in Rust...
         // (define (square x) (* x  x))
-        let f1 = Box::new(Exp::Symbol("define".to_string()));
-        let y = Box::new(Exp::Symbol("square".to_string()));
-        let z = Box::new(Exp::Symbol("x".to_string()));
-        let a = Box::new(Exp::Symbol("*".to_string()));
-        let b = Box::new(Exp::Symbol("x".to_string()));
-        let c = Box::new(Exp::Symbol("x".to_string()));
-        let d1 = Box::new(Pair::Nil);
-        let d2 = Box::new(Pair::Nil);
-        let d3 = Box::new(Pair::Nil);
+        let f1 = Rc::new(Exp::Symbol(Symbol::new("define")));
+        let y = Rc::new(Exp::Symbol(Symbol::new("square")));
+        let z = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let a = Rc::new(Exp::Symbol(Symbol::new("*")));
+        let b = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let c = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let d1 = Rc::new(Pair::Nil);
+        let d2 = Rc::new(Pair::Nil);
+        let d3 = Rc::new(Pair::Nil);
         // represent (* x x)
         let s1 = Pair::Cons(c, d1);
-        let s2 = Pair::Cons(b, Box::new(s1));
-        let t1 = Pair::Cons(a, Box::new(s2));
+        let s2 = Pair::Cons(b, Rc::new(s1));
+        let t1 = Pair::Cons(a, Rc::new(s2));
         let t2 = Exp::List(t1);
         let x4 = t2.clone();
-        let f3 = Box::new(t2);
+        let f3 = Rc::new(t2);
         // represent (square x)
         let s3 = Pair::Cons(z, d2);
         let x5 = s3.clone();
-        let t3 = Box::new(s3);
+        let t3 = Rc::new(s3);
         let t4 = Pair::Cons(y, t3);
         let v = Exp::List(t4);
         let x2 = v.clone();
-        let f2 = Box::new(v);
+        let f2 = Rc::new(v);
         // represent (define (square x) (* x x))
         let t5 = Pair::Cons(f3, d3);
         let x3 = t5.clone();
-        let t6 = Box::new(t5);
+        let t6 = Rc::new(t5);
         let t7 = Pair::Cons(f2, t6);
         let x1 = Exp::List(t7.clone());
-        let t8 = Box::new(t7);
+        let t8 = Rc::new(t7);
         let t9 = Pair::Cons(f1, t8);
         let exp = Exp::List(t9);
         if let Ok(Exp::Symbol(x)) = car(exp.clone()) {
@@ -604,9 +850,12 @@ mod tests {
         };
         assert_eq!(
             caadr(exp.clone()).unwrap(),
-            Exp::Symbol("square".to_string())
+            Exp::Symbol(Symbol::new("square"))
+        );
+        assert_eq!(
+            car(exp.clone()).unwrap(),
+            Exp::Symbol(Symbol::new("define"))
         );
-        assert_eq!(car(exp.clone()).unwrap(), Exp::Symbol("define".to_string()));
         assert_eq!(cdr(exp.clone()).unwrap(), x1);
         assert_eq!(cadr(exp.clone()).unwrap(), x2);
         assert_eq!(cddr(exp.clone()).unwrap(), Exp::List(x3));
@@ -617,72 +866,72 @@ mod tests {
 
     #[test]
     fn test_equlity() {
-        let f1 = Box::new(Exp::Symbol("define".to_string()));
-        let y = Box::new(Exp::Symbol("square".to_string()));
-        let z = Box::new(Exp::Symbol("x".to_string()));
-        let a = Box::new(Exp::Symbol("*".to_string()));
-        let b = Box::new(Exp::Symbol("x".to_string()));
-        let c = Box::new(Exp::Symbol("x".to_string()));
-        let d1 = Box::new(Pair::Nil);
-        let d2 = Box::new(Pair::Nil);
-        let d3 = Box::new(Pair::Nil);
+        let f1 = Rc::new(Exp::Symbol(Symbol::new("define")));
+        let y = Rc::new(Exp::Symbol(Symbol::new("square")));
+        let z = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let a = Rc::new(Exp::Symbol(Symbol::new("*")));
+        let b = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let c = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let d1 = Rc::new(Pair::Nil);
+        let d2 = Rc::new(Pair::Nil);
+        let d3 = Rc::new(Pair::Nil);
         // represent (* x x)
         let s1 = Pair::Cons(c, d1);
-        let s2 = Pair::Cons(b, Box::new(s1));
-        let t1 = Pair::Cons(a, Box::new(s2));
+        let s2 = Pair::Cons(b, Rc::new(s1));
+        let t1 = Pair::Cons(a, Rc::new(s2));
         let t2 = Exp::List(t1);
-        let f3 = Box::new(t2);
+        let f3 = Rc::new(t2);
         // represent (square x)
         let s3 = Pair::Cons(z, d2);
-        let t3 = Box::new(s3);
+        let t3 = Rc::new(s3);
         let t4 = Pair::Cons(y, t3);
         let v = Exp::List(t4);
-        let f2 = Box::new(v);
+        let f2 = Rc::new(v);
         // represent (define (square x) (* x x))
         let t5 = Pair::Cons(f3, d3);
-        let t6 = Box::new(t5);
+        let t6 = Rc::new(t5);
         let t7 = Pair::Cons(f2, t6);
-        let t8 = Box::new(t7);
+        let t8 = Rc::new(t7);
         let t9 = Pair::Cons(f1, t8);
         let exp = Exp::List(t9);
-        let ref lrh = Pair::Nil;
+        let lrh = &Pair::Nil;
         let rhs = &Pair::Nil;
         assert_eq!(lrh, rhs);
     }
 
     #[test]
     fn test_tagged_list() {
-        let f1 = Box::new(Exp::Symbol("define".to_string()));
-        let y = Box::new(Exp::Symbol("square".to_string()));
-        let z = Box::new(Exp::Symbol("x".to_string()));
-        let a = Box::new(Exp::Symbol("*".to_string()));
-        let b = Box::new(Exp::Symbol("x".to_string()));
-        let c = Box::new(Exp::Symbol("x".to_string()));
-        let d1 = Box::new(Pair::Nil);
-        let d2 = Box::new(Pair::Nil);
-        let d3 = Box::new(Pair::Nil);
+        let f1 = Rc::new(Exp::Symbol(Symbol::new("define")));
+        let y = Rc::new(Exp::Symbol(Symbol::new("square")));
+        let z = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let a = Rc::new(Exp::Symbol(Symbol::new("*")));
+        let b = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let c = Rc::new(Exp::Symbol(Symbol::new("x")));
+        let d1 = Rc::new(Pair::Nil);
+        let d2 = Rc::new(Pair::Nil);
+        let d3 = Rc::new(Pair::Nil);
         // represent (* x x)
         let s1 = Pair::Cons(c, d1);
-        let s2 = Pair::Cons(b, Box::new(s1));
-        let t1 = Pair::Cons(a, Box::new(s2));
+        let s2 = Pair::Cons(b, Rc::new(s1));
+        let t1 = Pair::Cons(a, Rc::new(s2));
         let t2 = Exp::List(t1);
-        let f3 = Box::new(t2);
+        let f3 = Rc::new(t2);
         // represent (square x)
         let s3 = Pair::Cons(z, d2);
-        let t3 = Box::new(s3);
+        let t3 = Rc::new(s3);
         let t4 = Pair::Cons(y, t3);
         let v = Exp::List(t4);
-        let f2 = Box::new(v);
+        let f2 = Rc::new(v);
         // represent (define (square x) (* x x))
         let t5 = Pair::Cons(f3, d3);
-        let t6 = Box::new(t5);
+        let t6 = Rc::new(t5);
         let t7 = Pair::Cons(f2, t6);
-        let t8 = Box::new(t7);
+        let t8 = Rc::new(t7);
         let t9 = Pair::Cons(f1, t8);
         let exp = Exp::List(t9);
-        let tag1 = "define";
+        let tag1 = Symbol::new("define");
         assert_eq!(is_tagged_list(exp.clone(), tag1), true);
-        assert_ne!(is_tagged_list(exp.clone(), "apple"), true);
+        assert_ne!(is_tagged_list(exp.clone(), Symbol::new("apple")), true);
     }
 
     #[test]
@@ -691,11 +940,11 @@ mod tests {
         let if_exp = data.if_expression;
         assert_eq!(is_if(if_exp.clone()), true);
 
-        let s1 = Exp::Symbol("if".to_string());
-        let s2 = Exp::Symbol("n".to_string());
+        let s1 = Exp::Symbol(Symbol::new("if"));
+        let s2 = Exp::Symbol(Symbol::new("n"));
         let s3 = Exp::Integer(1);
-        let s4 = Exp::Symbol("-".to_string());
-        let s5 = Exp::Symbol("=".to_string());
+        let s4 = Exp::Symbol(Symbol::new("-"));
+        let s5 = Exp::Symbol(Symbol::new("="));
         let x1 = scheme_list!(s5, s2.clone(), s3.clone());
         let x2 = scheme_list!(s4, s2.clone(), s3.clone());
         assert_eq!(if_predicate(if_exp.clone()), x1);
@@ -718,10 +967,10 @@ mod tests {
         let begin_exp = data.begin_expression;
         let t1 = Exp::Integer(5);
         let t2 = Exp::Integer(1);
-        let t3 = Exp::Symbol("set!".to_string());
-        let t4 = Exp::Symbol("begin".to_string());
-        let t5 = Exp::Symbol("x".to_string());
-        let t6 = Exp::Symbol("+".to_string());
+        let t3 = Exp::Symbol(Symbol::new("set!"));
+        let t4 = Exp::Symbol(Symbol::new("begin"));
+        let t5 = Exp::Symbol(Symbol::new("x"));
+        let t6 = Exp::Symbol(Symbol::new("+"));
         // (set! x 5)
         let y1 = scheme_list!(t3, t5.clone(), t1);
         // (+ x 1)
@@ -748,7 +997,7 @@ mod tests {
     fn test_application() {
         let data = generate_test_data();
         let app_exp = data.applicatioin_expressioin;
-        let p1 = Exp::Symbol("procedure".to_string());
+        let p1 = Exp::Symbol(Symbol::new("procedure"));
         let p2 = Exp::Integer(3);
         let p3 = Exp::Integer(4);
         assert_eq!(is_application(app_exp.clone()), true);
@@ -767,9 +1016,9 @@ mod tests {
         let lambda_exp = data.lambda_expression;
 
         // (lambda (x) (* x x))
-        let r1 = Exp::Symbol("lambda".to_string());
-        let r2 = Exp::Symbol("x".to_string());
-        let r3 = Exp::Symbol("*".to_string());
+        let r1 = Exp::Symbol(Symbol::new("lambda"));
+        let r2 = Exp::Symbol(Symbol::new("x"));
+        let r3 = Exp::Symbol(Symbol::new("*"));
 
         let parameters = scheme_list!(r2.clone());
         let body = scheme_list!(scheme_list!(r3, r2.clone(), r2.clone()));
@@ -782,26 +1031,22 @@ mod tests {
 
     #[test]
     fn test_procedure() {
-        // primitive procedure: ('primitive +)
-        // compound procedure: ('primitive (x y) (+ x y) env))
-        // env:  (((x y z) 1 2 3) ((u v) 4 5))
-        let frame = generate_test_frames().frame;
-        let env = Env(scheme_list!(frame));
-        let parameters = scheme_list!(Exp::Symbol("x".to_string()), Exp::Symbol("y".to_string()));
+        // primitive procedure: #<primitive +>
+        // compound procedure: #<procedure>, made by lambda
+        let parameters = scheme_list!(Exp::Symbol(Symbol::new("x")), Exp::Symbol(Symbol::new("y")));
         let body = scheme_list!(
-            Exp::Symbol("+".to_string()),
-            Exp::Symbol("x".to_string()),
-            Exp::Symbol("y".to_string())
-        );
-        let procedure = scheme_list!(
-            Exp::Symbol("procedure".to_string()),
-            parameters.clone(),
-            body.clone(),
-            env.0.clone()
+            Exp::Symbol(Symbol::new("+")),
+            Exp::Symbol(Symbol::new("x")),
+            Exp::Symbol(Symbol::new("y"))
         );
+        let procedure = make_procedure(parameters.clone(), body.clone());
         assert_eq!(is_compound_procedure(procedure.clone()), true);
         assert_eq!(procedure_parameters(procedure.clone()), parameters);
         assert_eq!(procedure_body(procedure.clone()), body);
+        assert_eq!(procedure_library(procedure.clone()), None);
+        // a quoted list that looks like a procedure is only data
+        let data = scheme_list!(Exp::Symbol(Symbol::new("procedure")), parameters, body);
+        assert_eq!(is_compound_procedure(data), false);
     }
 
     #[test]
@@ -810,7 +1055,7 @@ mod tests {
             scheme_list!(Exp::Integer(1), Exp::Integer(2)),
             scheme_list!(
                 Exp::FloatNumber(3.1),
-                Exp::Symbol("s".to_string()),
+                Exp::Symbol(Symbol::new("s")),
                 Exp::Integer(4)
             ),
             scheme_list!(Exp::Integer(5), Exp::Integer(6))
@@ -823,4 +1068,19 @@ mod tests {
         );
         assert_eq!(is_number_combination(x.clone()), true);
     }
+
+    #[test]
+    fn test_is_eqv_and_is_equal() {
+        let x = scheme_list!(Exp::Integer(1), Exp::FloatNumber(2.0));
+        let y = scheme_list!(Exp::Integer(1), Exp::FloatNumber(2.0));
+        assert_eq!(x.is_eq(&x.clone()), true);
+        assert_eq!(x.is_eq(&y), false);
+        assert_eq!(x.is_equal(&y), true);
+        assert_eq!(
+            Exp::FloatNumber(f64::NAN).is_eqv(&Exp::FloatNumber(f64::NAN)),
+            true
+        );
+        assert_eq!(Exp::FloatNumber(0.0).is_eqv(&Exp::FloatNumber(-0.0)), false);
+        assert_eq!(Exp::Integer(2).is_eqv(&Exp::FloatNumber(2.0)), false);
+    }
 }
@@ -0,0 +1,169 @@
+#[allow(clippy::module_inception)]
+pub mod symbol {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::sync::{LazyLock, Mutex};
+
+    /// An interned symbol. Two symbols with the same name share the same
+    /// id, so comparing symbols is an integer comparison.
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Symbol(u32);
+
+    // the table every thread takes ids from, so that a symbol means the
+    // same name on every thread
+    struct SymbolTable {
+        names: Vec<&'static str>,
+        ids: HashMap<&'static str, u32>,
+    }
+
+    // the names a thread has already looked up, so that interning a known
+    // name or reading one back does not take the lock of the shared table
+    #[derive(Default)]
+    struct LocalSymbols {
+        names: Vec<Option<&'static str>>,
+        ids: HashMap<&'static str, u32>,
+    }
+
+    // The keywords are interned first, in this order, so each one gets a
+    // constant whose id is its index in KEYWORDS.
+    macro_rules! keywords {
+        ($($name:ident: $text:literal,)*) => {
+            const KEYWORDS: &[&str] = &[$($text),*];
+            keywords!(@ids 0; $($name)*);
+        };
+        (@ids $id:expr;) => {};
+        (@ids $id:expr; $name:ident $($rest:ident)*) => {
+            pub const $name: Symbol = Symbol($id);
+            keywords!(@ids $id + 1; $($rest)*);
+        };
+    }
+
+    // symbols the evaluator looks for
+    keywords! {
+        QUOTE: "quote",
+        SET: "set!",
+        DEFINE: "define",
+        LAMBDA: "lambda",
+        IF: "if",
+        BEGIN: "begin",
+        APPLY: "apply",
+        DEFINE_RECORD_TYPE: "define-record-type",
+        PARAMETERIZE: "parameterize",
+        INCLUDE: "include",
+        DEFINE_LIBRARY: "define-library",
+        IMPORT: "import",
+        THE_ENVIRONMENT: "the-environment",
+        TIME: "time",
+        EXPORT: "export",
+    }
+
+    static SYMBOL_TABLE: LazyLock<Mutex<SymbolTable>> = LazyLock::new(|| {
+        let mut table = SymbolTable {
+            names: vec![],
+            ids: HashMap::new(),
+        };
+        for name in KEYWORDS.iter() {
+            table.intern(name);
+        }
+        Mutex::new(table)
+    });
+
+    thread_local! {
+        static LOCAL_SYMBOLS: RefCell<LocalSymbols> = RefCell::new(LocalSymbols::default());
+    }
+
+    impl SymbolTable {
+        fn intern(&mut self, name: &str) -> (u32, &'static str) {
+            if let Some((name, id)) = self.ids.get_key_value(name) {
+                (*id, *name)
+            } else {
+                // interned names live as long as the interpreter
+                let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+                let id = self.names.len() as u32;
+                self.names.push(name);
+                self.ids.insert(name, id);
+                (id, name)
+            }
+        }
+    }
+
+    impl LocalSymbols {
+        fn remember(&mut self, id: u32, name: &'static str) {
+            let index = id as usize;
+            if self.names.len() <= index {
+                self.names.resize(index + 1, None);
+            }
+            self.names[index] = Some(name);
+            self.ids.insert(name, id);
+        }
+    }
+
+    #[allow(dead_code)]
+    impl Symbol {
+        pub fn new(name: &str) -> Symbol {
+            LOCAL_SYMBOLS.with(|local| {
+                if let Some(id) = local.borrow().ids.get(name) {
+                    return Symbol(*id);
+                }
+                let (id, name) = SYMBOL_TABLE.lock().unwrap().intern(name);
+                local.borrow_mut().remember(id, name);
+                Symbol(id)
+            })
+        }
+
+        /// The index of the symbol in the table, for tables indexed by symbol.
+        pub fn id(&self) -> usize {
+            self.0 as usize
+        }
+
+        pub fn as_str(&self) -> &'static str {
+            LOCAL_SYMBOLS.with(|local| {
+                if let Some(Some(name)) = local.borrow().names.get(self.id()) {
+                    return *name;
+                }
+                let name = SYMBOL_TABLE.lock().unwrap().names[self.id()];
+                local.borrow_mut().remember(self.0, name);
+                name
+            })
+        }
+    }
+
+    impl fmt::Display for Symbol {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.as_str())
+        }
+    }
+
+    impl fmt::Debug for Symbol {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self.as_str())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::symbol::{Symbol, DEFINE, EXPORT, QUOTE};
+
+    #[test]
+    fn test_intern() {
+        assert_eq!(Symbol::new("square"), Symbol::new("square"));
+        assert_ne!(Symbol::new("square"), Symbol::new("cube"));
+        assert_eq!(Symbol::new("define"), DEFINE);
+        assert_eq!(Symbol::new("square").as_str(), "square");
+        assert_eq!(format!("{}", Symbol::new("set-car!")), "set-car!");
+        assert_eq!(QUOTE.as_str(), "quote");
+        assert_eq!(EXPORT.as_str(), "export");
+        // ids come from one table, a symbol means the same on every thread
+        let other = std::thread::spawn(|| (Symbol::new("square"), Symbol::new("cube")))
+            .join()
+            .unwrap();
+        assert_eq!(other, (Symbol::new("square"), Symbol::new("cube")));
+        let fresh = std::thread::spawn(|| Symbol::new("made-on-another-thread"))
+            .join()
+            .unwrap();
+        assert_eq!(fresh.as_str(), "made-on-another-thread");
+        assert_eq!(Symbol::new("made-on-another-thread"), fresh);
+    }
+}
@@ -1,10 +1,9 @@
 #![allow(unused_variables)]
 pub mod tools {
+    use crate::core_of_interpreter::core_of_interpreter::{Exp, Pair};
     use crate::represent::represent::{car, cdr};
-    use crate::{
-        core_of_interpreter::core_of_interpreter::{Exp, Pair},
-        scheme_list,
-    };
+    use crate::symbol::symbol::Symbol;
+    use std::rc::Rc;
 
     #[macro_export]
     macro_rules! scheme_list {
@@ -24,17 +23,17 @@ pub mod tools {
     pub fn scheme_cons(lhs: Exp, rhs: Exp) -> Exp {
         match rhs {
             Exp::List(x) => {
-                let s1 = Box::new(lhs);
-                let s2 = Box::new(x);
+                let s1 = Rc::new(lhs);
+                let s2 = Rc::new(x);
                 let s3 = Pair::Cons(s1, s2);
                 Exp::List(s3)
             }
             _ => {
-                let s1 = Box::new(Pair::Nil);
-                let s2 = Box::new(rhs);
+                let s1 = Rc::new(Pair::Nil);
+                let s2 = Rc::new(rhs);
                 let s3 = Pair::Cons(s2, s1);
-                let s4 = Box::new(s3);
-                let s5 = Box::new(lhs);
+                let s4 = Rc::new(s3);
+                let s5 = Rc::new(lhs);
                 Exp::List(Pair::Cons(s5, s4))
             }
         }
@@ -56,7 +55,7 @@ pub mod tools {
     #[allow(dead_code)]
     pub fn set_car(x: Exp, y: Exp) -> Result<Exp, &'static str> {
         if let Exp::List(Pair::Cons(lhs, rhs)) = x {
-            Ok(Exp::List(Pair::Cons(Box::new(y), rhs)))
+            Ok(Exp::List(Pair::Cons(Rc::new(y), rhs)))
         } else {
             Err("error happens!")
         }
@@ -65,7 +64,7 @@ pub mod tools {
     #[allow(dead_code)]
     pub fn set_cdr(x: Exp, y: Exp) -> Result<Exp, &'static str> {
         if let Exp::List(Pair::Cons(lhs, rhs)) = x {
-            Ok(scheme_cons(*lhs, y))
+            Ok(scheme_cons((*lhs).clone(), y))
         } else {
             Err("error happens!")
         }
@@ -79,6 +78,63 @@ pub mod tools {
         }
     }
 
+    #[allow(dead_code)]
+    pub fn reverse(exp: Exp) -> Exp {
+        let mut result = Exp::List(Pair::Nil);
+        let mut rest = exp;
+        while rest.is_pair() {
+            result = scheme_cons(car(rest.clone()).unwrap(), result);
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    // (list-tail '(1 2 3) 1) => (2 3)
+    #[allow(dead_code)]
+    pub fn list_tail(exp: Exp, k: i64) -> Exp {
+        let mut rest = exp;
+        for _ in 0..k {
+            if !rest.is_pair() {
+                panic!("index out of range!");
+            }
+            rest = cdr(rest).unwrap();
+        }
+        rest
+    }
+
+    #[allow(dead_code)]
+    pub fn last_pair(exp: Exp) -> Exp {
+        let mut rest = exp;
+        if !rest.is_pair() {
+            panic!("not a pair!");
+        }
+        while cdr(rest.clone()).unwrap().is_pair() {
+            rest = cdr(rest).unwrap();
+        }
+        rest
+    }
+
+    #[allow(dead_code)]
+    pub fn list_to_vec(exp: Exp) -> Vec<Exp> {
+        let mut result = vec![];
+        let mut rest = exp;
+        while rest.is_pair() {
+            result.push(car(rest.clone()).unwrap());
+            rest = cdr(rest).unwrap();
+        }
+        result
+    }
+
+    #[allow(dead_code)]
+    pub fn vec_to_list(x: &[Exp]) -> Exp {
+        let mut result = Exp::List(Pair::Nil);
+        for item in x.iter().rev() {
+            result = scheme_cons(item.clone(), result);
+        }
+        result
+    }
+
+    #[allow(dead_code)]
     #[derive(Debug, Clone)]
     pub struct TestData {
         pub if_expression: Exp,
@@ -90,11 +146,11 @@ pub mod tools {
     #[allow(dead_code)]
     pub fn generate_test_data() -> TestData {
         // (if (= n 1)  1  (-  n  1))
-        let s1 = Exp::Symbol("if".to_string());
-        let s2 = Exp::Symbol("n".to_string());
+        let s1 = Exp::Symbol(Symbol::new("if"));
+        let s2 = Exp::Symbol(Symbol::new("n"));
         let s3 = Exp::Integer(1);
-        let s4 = Exp::Symbol("-".to_string());
-        let s5 = Exp::Symbol("=".to_string());
+        let s4 = Exp::Symbol(Symbol::new("-"));
+        let s5 = Exp::Symbol(Symbol::new("="));
         let x1 = scheme_list!(s5, s2.clone(), s3.clone());
         let x2 = scheme_list!(s4, s2.clone(), s3.clone());
         let if_exp = scheme_list!(s1, x1, s3.clone(), x2);
@@ -102,38 +158,38 @@ pub mod tools {
         // (begin (set! x 5) (+ x 1))
         let t1 = Exp::Integer(5);
         let t2 = Exp::Integer(1);
-        let t3 = Exp::Symbol("set!".to_string());
-        let t4 = Exp::Symbol("begin".to_string());
-        let t5 = Exp::Symbol("x".to_string());
-        let t6 = Exp::Symbol("+".to_string());
+        let t3 = Exp::Symbol(Symbol::new("set!"));
+        let t4 = Exp::Symbol(Symbol::new("begin"));
+        let t5 = Exp::Symbol(Symbol::new("x"));
+        let t6 = Exp::Symbol(Symbol::new("+"));
         let y1 = scheme_list!(t3, t5.clone(), t1);
         let y2 = scheme_list!(t6, t5.clone(), t2);
         let begin_exp = scheme_list!(t4, y1, y2);
 
         // (lambda (x) (* x x))
-        let r1 = Exp::Symbol("lambda".to_string());
-        let r2 = Exp::Symbol("x".to_string());
-        let r3 = Exp::Symbol("*".to_string());
+        let r1 = Exp::Symbol(Symbol::new("lambda"));
+        let r2 = Exp::Symbol(Symbol::new("x"));
+        let r3 = Exp::Symbol(Symbol::new("*"));
         let null = Exp::List(Pair::Nil);
         let r4 = scheme_cons(r2.clone(), null);
         let r5 = scheme_list!(r3, r2.clone(), r2.clone());
         let lambda_exp = scheme_list!(r1, r4, r5);
 
         // (procedure 3 4)
-        let p1 = Exp::Symbol("procedure".to_string());
+        let p1 = Exp::Symbol(Symbol::new("procedure"));
         let p2 = Exp::Integer(3);
         let p3 = Exp::Integer(4);
         let app_exp = scheme_list!(p1, p2, p3);
 
-        let data = TestData {
+        TestData {
             if_expression: if_exp,
             begin_expression: begin_exp,
             applicatioin_expressioin: app_exp,
             lambda_expression: lambda_exp,
-        };
-        data
+        }
     }
 
+    #[allow(dead_code)]
     pub struct Frames {
         pub variables: Exp,
         pub values: Exp,
@@ -145,23 +201,23 @@ pub mod tools {
     impl Frames {
         fn new(variables: Exp, values: Exp, frame: Exp, extended_frame: Exp) -> Self {
             Frames {
-                variables: variables,
-                values: values,
-                frame: frame,
-                extended_frame: extended_frame,
+                variables,
+                values,
+                frame,
+                extended_frame,
             }
         }
     }
 
     #[allow(dead_code)]
     pub fn generate_test_frames() -> Frames {
-        let x = Exp::Symbol("x".to_string());
-        let y = Exp::Symbol("y".to_string());
-        let z = Exp::Symbol("z".to_string());
+        let x = Exp::Symbol(Symbol::new("x"));
+        let y = Exp::Symbol(Symbol::new("y"));
+        let z = Exp::Symbol(Symbol::new("z"));
         let one = Exp::Integer(1);
         let two = Exp::Integer(2);
         let three = Exp::Integer(3);
-        let a = Exp::Symbol("a".to_string());
+        let a = Exp::Symbol(Symbol::new("a"));
         let four = Exp::Integer(4);
         let variables = scheme_list!(x.clone(), y.clone(), z.clone());
         let values = scheme_list!(one.clone(), two.clone(), three.clone());
@@ -174,8 +230,11 @@ pub mod tools {
 }
 #[cfg(test)]
 mod test {
-    use super::tools::{append, list_length, scheme_cons, set_car, set_cdr};
+    use super::tools::{
+        append, last_pair, list_length, list_tail, reverse, scheme_cons, set_car, set_cdr,
+    };
     use crate::represent::represent::{caadr, cadr, cddr};
+    use crate::symbol::symbol::Symbol;
     use crate::{
         core_of_interpreter::core_of_interpreter::{Exp, Pair},
         scheme_list,
@@ -186,8 +245,8 @@ mod test {
         // ((lambda (x) (+ x x)) 4) =>  8
         // lambda prameters: (x)
         // lambda body: ((+ x x))
-        let plus = Exp::Symbol("+".to_string());
-        let x = Exp::Symbol("x".to_string());
+        let plus = Exp::Symbol(Symbol::new("+"));
+        let x = Exp::Symbol(Symbol::new("x"));
         let null = Exp::List(Pair::Nil);
         //  parameters: (x)
         let parameters = scheme_cons(x.clone(), null.clone());
@@ -221,10 +280,10 @@ mod test {
     #[test]
     fn test_set_cdr() {
         // ("hello" "world")  -> ("hello" "fool")
-        let hello = Exp::Symbol("hello".to_string());
-        let world = Exp::Symbol("world".to_string());
+        let hello = Exp::Symbol(Symbol::new("hello"));
+        let world = Exp::Symbol(Symbol::new("world"));
 
-        let fool = Exp::Symbol("fool".to_string());
+        let fool = Exp::Symbol(Symbol::new("fool"));
         let s1 = scheme_list!(hello.clone(), world);
         let s2 = scheme_list!(hello.clone(), fool.clone());
         assert_eq!(s2, set_cdr(s1, fool).unwrap());
@@ -232,9 +291,9 @@ mod test {
 
     #[test]
     fn test_set_car() {
-        let hello = Exp::Symbol("hello".to_string());
-        let world = Exp::Symbol("world".to_string());
-        let fool = Exp::Symbol("fool".to_string());
+        let hello = Exp::Symbol(Symbol::new("hello"));
+        let world = Exp::Symbol(Symbol::new("world"));
+        let fool = Exp::Symbol(Symbol::new("fool"));
 
         let s1 = scheme_list!(hello.clone(), world.clone());
         let s2 = scheme_list!(fool.clone(), world.clone());
@@ -250,4 +309,20 @@ mod test {
         assert_eq!(list_length(s), 3);
         assert_eq!(list_length(Exp::List(Pair::Nil)), 0);
     }
+
+    #[test]
+    fn test_reverse_and_list_tail() {
+        let s = scheme_list!(Exp::Integer(1), Exp::Integer(2), Exp::Integer(3));
+        assert_eq!(
+            reverse(s.clone()),
+            scheme_list!(Exp::Integer(3), Exp::Integer(2), Exp::Integer(1))
+        );
+        assert_eq!(reverse(Exp::List(Pair::Nil)), Exp::List(Pair::Nil));
+        assert_eq!(
+            list_tail(s.clone(), 1),
+            scheme_list!(Exp::Integer(2), Exp::Integer(3))
+        );
+        assert_eq!(list_tail(s.clone(), 3), Exp::List(Pair::Nil));
+        assert_eq!(last_pair(s), scheme_list!(Exp::Integer(3)));
+    }
 }